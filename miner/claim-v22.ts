@@ -114,7 +114,7 @@ async function main() {
         { pubkey: solAddr, isSigner: false, isWritable: true },
         { pubkey: mintAddr, isSigner: false, isWritable: true },
         { pubkey: ata, isSigner: false, isWritable: true },
-        { pubkey: kp.publicKey, isSigner: false, isWritable: true }, // rent_recipient
+        { pubkey: kp.publicKey, isSigner: false, isWritable: true }, // payee
         { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
       ],
       data,
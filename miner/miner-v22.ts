@@ -182,7 +182,7 @@ async function claim(epoch: bigint) {
       { pubkey: solAddr, isSigner: false, isWritable: true },
       { pubkey: mintAddr, isSigner: false, isWritable: true },
       { pubkey: recipientAta, isSigner: false, isWritable: true },
-      { pubkey: wallet.publicKey, isSigner: false, isWritable: true }, // rent_recipient
+      { pubkey: wallet.publicKey, isSigner: false, isWritable: true }, // payee
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
     ],
     data: disc('claim'),
@@ -0,0 +1,11 @@
+//! Text verification and word derivation shared by the Proof of Inference
+//! program and off-chain miners.
+//!
+//! `no_std`, no Solana dependencies. The `anchor` feature adds the Borsh and
+//! `InitSpace` derives the program needs to store `TextRules` on-chain.
+
+// Anchor's derives need std, which the program links anyway.
+#![cfg_attr(not(any(test, feature = "anchor")), no_std)]
+
+pub mod verify;
+pub mod words;
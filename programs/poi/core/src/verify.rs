@@ -0,0 +1,1706 @@
+//! Text constraint verification for Proof of Inference.
+//!
+//! Single O(n) pass, no_std compatible, zero heap allocation.
+//! Checks: length, required words (with word boundaries), sentence structure,
+//! vowel/space ratios, average word length, consonant clusters, bigram/trigram
+//! frequency, byte diversity.
+//! The pass stops early on a consonant run past 5, a whitespace run past 2,
+//! and at each sentence end once the vowel or space ratio can no longer be
+//! met, so most bad texts cost a fraction of a full scan.
+
+#[cfg(feature = "anchor")]
+use anchor_lang::prelude::borsh;
+
+use crate::words::MAX_REQUIRED;
+
+/// Default sentence cap.
+pub const DEFAULT_MAX_SENTENCES: u32 = 50;
+/// Upper bound for a configured sentence cap (keeps the dedup false-positive
+/// rate below ~0.4%; see `SentenceFilter`).
+pub const MAX_SENTENCE_CAP: u32 = 100;
+/// Sentence-dedup Bloom filter size in bits (32 × u64).
+pub const BLOOM_BITS: usize = 2048;
+/// Bit positions set per sentence.
+pub const BLOOM_HASHES: u64 = 6;
+/// Largest supported repeated-phrase window (words).
+pub const MAX_PHRASE_WINDOW: u8 = 8;
+/// Number of recent phrase-window hashes remembered.
+pub const PHRASE_RING: usize = 64;
+/// Upper bound for `TextRules::vowel_grace` (percentage points).
+pub const MAX_VOWEL_GRACE: u8 = 5;
+/// Default digit ceiling for new deployments (percent of bytes).
+pub const DEFAULT_MAX_DIGIT_PCT: u8 = 5;
+/// Default vowel band (percent of letters) and space band (percent of bytes).
+pub const DEFAULT_VOWEL_PCT: (u8, u8) = (30, 48);
+pub const DEFAULT_SPACE_PCT: (u8, u8) = (12, 22);
+/// Default distinct byte values a whole text must use.
+pub const DEFAULT_MIN_UNIQUE_BYTES: u8 = 28;
+/// Shortest text `verify_text` accepts, in bytes.
+pub const MIN_TEXT_LEN: usize = 256;
+/// Upper bound for `TextRulesExt::lowercase_start_tolerance`: past a few
+/// lowercase starts `require_capitals` no longer constrains much.
+pub const MAX_LOWERCASE_START_TOLERANCE: u8 = 5;
+/// Smallest nonzero `TextRulesExt::bytes_per_extra_match`.
+pub const MIN_BYTES_PER_EXTRA_MATCH: u16 = 100;
+/// Longest allowed run of whitespace bytes (a space after a full stop, or a
+/// blank line between paragraphs).
+pub const MAX_WHITESPACE_RUN: u32 = 2;
+
+/// Tunable verification parameters (stored on-chain in `MineState.text_rules`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "anchor",
+    derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize, anchor_lang::InitSpace)
+)]
+pub struct TextRules {
+    /// Maximum sentence count (bounds compute). At most `MAX_SENTENCE_CAP`.
+    pub max_sentences: u32,
+    /// Minimum occurrences of each trigram ("the", "ing", " and", "ion"). 0 disables.
+    pub min_trigrams: u32,
+    /// Words per repeated-phrase window. 0 disables.
+    pub phrase_window: u8,
+    /// Maximum occurrences of any phrase window.
+    pub max_phrase_repeats: u8,
+    /// Reject texts whose second half is the byte-reversal of the first.
+    pub reject_mirrored: bool,
+    /// Reject repeated sentences. Off skips the Bloom filter entirely.
+    pub dedup_sentences: bool,
+    /// Percentage points the 30–48% vowel band may widen to absorb vowel-poor
+    /// (or vowel-rich) required words. At most `MAX_VOWEL_GRACE`; 0 disables.
+    pub vowel_grace: u8,
+    /// The first required word must be matched in the text's first sentence.
+    pub anchor_first_word: bool,
+}
+
+/// Preset ids for `TextRules::preset`.
+pub const PRESET_STANDARD: u8 = 0;
+pub const PRESET_STRICT: u8 = 1;
+pub const PRESET_LENIENT: u8 = 2;
+pub const PRESET_TESTING: u8 = 3;
+
+/// Rules added after `TextRules`, whose size is fixed inside the MineState
+/// layout. MineState stores each field on its own, appended by the layout
+/// version that added it. `BASE` checks exactly what `TextRules` alone does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextRulesExt {
+    /// Required-word boundaries, one of `BOUNDARY_*`.
+    pub boundary_mode: u8,
+    /// Most digits allowed, in percent of the text's bytes. 0 disables.
+    pub max_digit_pct: u8,
+    /// Distinct sentence endings (`.`, `!`, `?`) the text must use, 0–3.
+    /// 0 or 1 add nothing to the required question.
+    pub min_ending_variety: u8,
+    /// Sentences must start (first letter) with a capital.
+    pub require_capitals: bool,
+    /// Lowercase sentence starts tolerated under `require_capitals`
+    /// ("e.g. this" ends a sentence early), at most `MAX_LOWERCASE_START_TOLERANCE`.
+    pub lowercase_start_tolerance: u8,
+    /// Vowels as a percent of letters, before any `vowel_grace`.
+    pub min_vowel_pct: u8,
+    pub max_vowel_pct: u8,
+    /// Spaces as a percent of the text's bytes.
+    pub min_space_pct: u8,
+    pub max_space_pct: u8,
+    /// Distinct byte values the whole text must use, 1–128.
+    pub min_unique_bytes: u8,
+    /// Distinct byte values each sentence must use, at most `min_unique_bytes`.
+    /// 0 disables.
+    pub min_sentence_unique_bytes: u8,
+    /// Each required word must occur once more per this many bytes past
+    /// `MIN_TEXT_LEN`, so padding a text means using the words more. 0 disables.
+    pub bytes_per_extra_match: u16,
+    /// Double quotes (`"`) must come in pairs.
+    pub require_balanced_quotes: bool,
+}
+
+impl TextRulesExt {
+    /// Nothing beyond `TextRules`.
+    pub const BASE: TextRulesExt = TextRulesExt {
+        boundary_mode: BOUNDARY_ALPHA,
+        max_digit_pct: 0,
+        min_ending_variety: 0,
+        require_capitals: false,
+        lowercase_start_tolerance: 0,
+        min_vowel_pct: DEFAULT_VOWEL_PCT.0,
+        max_vowel_pct: DEFAULT_VOWEL_PCT.1,
+        min_space_pct: DEFAULT_SPACE_PCT.0,
+        max_space_pct: DEFAULT_SPACE_PCT.1,
+        min_unique_bytes: DEFAULT_MIN_UNIQUE_BYTES,
+        min_sentence_unique_bytes: 0,
+        bytes_per_extra_match: 0,
+        require_balanced_quotes: false,
+    };
+
+    /// New deployments.
+    pub const DEFAULT: TextRulesExt = TextRulesExt {
+        boundary_mode: BOUNDARY_ALPHA,
+        max_digit_pct: DEFAULT_MAX_DIGIT_PCT,
+        min_ending_variety: 0,
+        require_capitals: true,
+        lowercase_start_tolerance: 1,
+        min_vowel_pct: DEFAULT_VOWEL_PCT.0,
+        max_vowel_pct: DEFAULT_VOWEL_PCT.1,
+        min_space_pct: DEFAULT_SPACE_PCT.0,
+        max_space_pct: DEFAULT_SPACE_PCT.1,
+        min_unique_bytes: DEFAULT_MIN_UNIQUE_BYTES,
+        min_sentence_unique_bytes: 0,
+        bytes_per_extra_match: 0,
+        require_balanced_quotes: false,
+    };
+
+    /// Sanity bounds for governance-supplied rules.
+    pub fn is_valid(&self) -> bool {
+        is_valid_boundary_mode(self.boundary_mode)
+            && self.max_digit_pct <= 100
+            && self.min_ending_variety <= 3
+            && self.lowercase_start_tolerance <= MAX_LOWERCASE_START_TOLERANCE
+            && self.ratios_valid()
+            && self.diversity_valid()
+            && (self.bytes_per_extra_match == 0 || self.bytes_per_extra_match >= MIN_BYTES_PER_EXTRA_MATCH)
+    }
+
+    /// Occurrences each required word needs in a `len`-byte text: 1, plus one per
+    /// `bytes_per_extra_match` bytes past `MIN_TEXT_LEN` when that is set.
+    pub fn required_matches(&self, len: usize) -> usize {
+        match self.bytes_per_extra_match {
+            0 => 1,
+            step => 1 + len.saturating_sub(MIN_TEXT_LEN) / step as usize,
+        }
+    }
+
+    /// `min_unique_bytes` is 1–128 (only ASCII passes, so no text has more)
+    /// and the per-sentence floor is at most it.
+    pub fn diversity_valid(&self) -> bool {
+        (1..=128).contains(&self.min_unique_bytes) && self.min_sentence_unique_bytes <= self.min_unique_bytes
+    }
+
+    /// Each band is `low < high <= 100`.
+    pub fn ratios_valid(&self) -> bool {
+        self.min_vowel_pct < self.max_vowel_pct
+            && self.max_vowel_pct <= 100
+            && self.min_space_pct < self.max_space_pct
+            && self.max_space_pct <= 100
+    }
+}
+
+/// Required-word boundary modes (`TextRulesExt::boundary_mode`): which neighbouring
+/// bytes join a match to a longer word and so void it.
+/// Letters (and letter-apostrophe, "o'clock"): "the" not in "theory", but in "the2".
+pub const BOUNDARY_ALPHA: u8 = 0;
+/// Letters and digits: "the" in neither "theory" nor "the2".
+pub const BOUNDARY_ALPHANUMERIC: u8 = 1;
+/// Anything but whitespace: the word must stand between spaces (or the text's
+/// ends), so "the," and "the." don't count either.
+pub const BOUNDARY_WHITESPACE: u8 = 2;
+
+pub fn is_valid_boundary_mode(mode: u8) -> bool {
+    mode <= BOUNDARY_WHITESPACE
+}
+
+impl TextRules {
+    /// Standard production rules.
+    pub const DEFAULT: TextRules = TextRules {
+        max_sentences: DEFAULT_MAX_SENTENCES,
+        min_trigrams: 1,
+        phrase_window: 4,
+        max_phrase_repeats: 2,
+        reject_mirrored: true,
+        dedup_sentences: true,
+        vowel_grace: 2,
+        anchor_first_word: false,
+    };
+
+    /// Tighter rules for networks seeing template spam.
+    pub const STRICT: TextRules = TextRules {
+        max_sentences: 30,
+        min_trigrams: 2,
+        phrase_window: 3,
+        max_phrase_repeats: 2,
+        reject_mirrored: true,
+        dedup_sentences: true,
+        vowel_grace: 1,
+        anchor_first_word: false,
+    };
+
+    /// Looser rules that only keep the core structure checks.
+    pub const LENIENT: TextRules = TextRules {
+        max_sentences: DEFAULT_MAX_SENTENCES,
+        min_trigrams: 0,
+        phrase_window: 6,
+        max_phrase_repeats: 3,
+        reject_mirrored: true,
+        dedup_sentences: true,
+        vowel_grace: 3,
+        anchor_first_word: false,
+    };
+
+    /// Most permissive rules, for devnet and client integration tests.
+    pub const TESTING: TextRules = TextRules {
+        max_sentences: DEFAULT_MAX_SENTENCES,
+        min_trigrams: 0,
+        phrase_window: 0,
+        max_phrase_repeats: 0,
+        reject_mirrored: false,
+        dedup_sentences: false,
+        vowel_grace: 5,
+        anchor_first_word: false,
+    };
+
+    /// Look up a preset by id.
+    pub fn preset(id: u8) -> Option<TextRules> {
+        match id {
+            PRESET_STANDARD => Some(Self::DEFAULT),
+            PRESET_STRICT => Some(Self::STRICT),
+            PRESET_LENIENT => Some(Self::LENIENT),
+            PRESET_TESTING => Some(Self::TESTING),
+            _ => None,
+        }
+    }
+
+    /// Sanity bounds for governance-supplied rules.
+    pub fn is_valid(&self) -> bool {
+        self.max_sentences >= 2
+            && self.max_sentences <= MAX_SENTENCE_CAP
+            && self.phrase_window <= MAX_PHRASE_WINDOW
+            && (self.phrase_window == 0 || self.max_phrase_repeats >= 1)
+            && self.vowel_grace <= MAX_VOWEL_GRACE
+    }
+}
+
+impl Default for TextRules {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// FNV-1a 64-bit hash for sentence dedup (two seeds → 128-bit effective)
+fn simple_hash(data: &[u8]) -> (u64, u64) {
+    let mut h1: u64 = 0xcbf29ce484222325;
+    let mut h2: u64 = 0x6c62272e07bb0142;
+    let mut i = 0;
+    while i < data.len() {
+        let b = data[i] as u64;
+        h1 ^= b;
+        h1 = h1.wrapping_mul(0x100000001b3);
+        h2 ^= b;
+        h2 = h2.wrapping_mul(0x100000001b3);
+        i += 1;
+    }
+    (h1, h2)
+}
+
+/// Sentence-dedup Bloom filter: 2048 bits, k = 6 positions derived from the
+/// two FNV seeds (`h1 + j*h2`). Coverage does not depend on sentence count.
+///
+/// A false positive rejects a valid text. Probability that a text with n
+/// distinct sentences is falsely rejected: n=10 ≈ 6e-10, n=20 ≈ 8e-8,
+/// n=50 ≈ 5e-5, n=100 ≈ 4e-3.
+pub struct SentenceFilter {
+    bits: [u64; BLOOM_BITS / 64],
+}
+
+impl SentenceFilter {
+    pub fn new() -> Self {
+        SentenceFilter { bits: [0; BLOOM_BITS / 64] }
+    }
+
+    /// Insert a sentence. Returns false if it was (probably) already present.
+    pub fn insert(&mut self, sentence: &[u8]) -> bool {
+        let (h1, h2) = simple_hash(sentence);
+        let mut fresh = false;
+        let mut j = 0;
+        while j < BLOOM_HASHES {
+            let bit = (h1.wrapping_add(j.wrapping_mul(h2)) % BLOOM_BITS as u64) as usize;
+            let mask = 1u64 << (bit & 63);
+            if self.bits[bit >> 6] & mask == 0 {
+                fresh = true;
+                self.bits[bit >> 6] |= mask;
+            }
+            j += 1;
+        }
+        fresh
+    }
+}
+
+impl Default for SentenceFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Repeated-phrase detector: hashes each window of N consecutive words
+/// (the byte span from the first word's start to the last word's end) and
+/// keeps the most recent `PHRASE_RING` window hashes in a fixed ring.
+struct PhraseTracker {
+    starts: [usize; MAX_PHRASE_WINDOW as usize],
+    words: usize,
+    ring: [u64; PHRASE_RING],
+    ring_len: usize,
+}
+
+impl PhraseTracker {
+    fn new() -> Self {
+        PhraseTracker {
+            starts: [0; MAX_PHRASE_WINDOW as usize],
+            words: 0,
+            ring: [0; PHRASE_RING],
+            ring_len: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn word_start(&mut self, pos: usize) {
+        self.starts[self.words % MAX_PHRASE_WINDOW as usize] = pos;
+    }
+
+    /// Close the current word at `end`. Returns false once some window
+    /// has been seen more than `max_repeats` times.
+    fn word_end(&mut self, text: &[u8], end: usize, window: u8, max_repeats: u8) -> bool {
+        self.words += 1;
+        let n = window as usize;
+        if n == 0 || self.words < n {
+            return true;
+        }
+        let start = self.starts[(self.words - n) % MAX_PHRASE_WINDOW as usize];
+        let h = simple_hash(&text[start..end]).0;
+
+        let filled = self.ring_len.min(PHRASE_RING);
+        let mut seen: u32 = 0;
+        let mut j = 0;
+        while j < filled {
+            if self.ring[j] == h {
+                seen += 1;
+            }
+            j += 1;
+        }
+        if seen >= max_repeats as u32 {
+            return false;
+        }
+        self.ring[self.ring_len % PHRASE_RING] = h;
+        self.ring_len += 1;
+        true
+    }
+}
+
+/// True if the text reads the same forwards and backwards byte-for-byte
+/// (i.e. the first half is the byte-reversal of the second half).
+pub fn is_mirrored(text: &[u8]) -> bool {
+    let len = text.len();
+    if len < 2 {
+        return false;
+    }
+    let mut lo = 0;
+    let mut hi = len - 1;
+    while lo < hi {
+        if text[lo] != text[hi] {
+            return false;
+        }
+        lo += 1;
+        hi -= 1;
+    }
+    true
+}
+
+#[inline(always)]
+fn is_alpha(b: u8) -> bool {
+    b.is_ascii_alphabetic()
+}
+
+/// Apostrophes sit inside words ("don't", "it's"): they neither end a word
+/// nor break a consonant run.
+#[inline(always)]
+fn is_apostrophe(b: u8) -> bool {
+    b == b'\''
+}
+
+/// Whether the hyphen at `i` sits between two letters or digits.
+#[inline(always)]
+fn hyphen_joins(text: &[u8], i: usize) -> bool {
+    i > 0 && i + 1 < text.len() && text[i - 1].is_ascii_alphanumeric() && text[i + 1].is_ascii_alphanumeric()
+}
+
+/// Whether `b` next to a required word makes it part of a longer word.
+#[inline(always)]
+fn joins_word(b: u8, mode: u8) -> bool {
+    match mode {
+        BOUNDARY_ALPHANUMERIC => b.is_ascii_alphanumeric(),
+        BOUNDARY_WHITESPACE => !is_whitespace(b),
+        _ => is_alpha(b),
+    }
+}
+
+/// Whether the byte before `start` continues a word: a joining byte, or an
+/// apostrophe after one ("o'clock"). A leading quote mark doesn't.
+#[inline(always)]
+fn continues_word_before(text: &[u8], start: usize, mode: u8) -> bool {
+    match start {
+        0 => false,
+        1 => joins_word(text[0], mode),
+        _ => {
+            joins_word(text[start - 1], mode)
+                || (is_apostrophe(text[start - 1]) && joins_word(text[start - 2], mode))
+        }
+    }
+}
+
+#[inline(always)]
+fn to_lower(b: u8) -> u8 {
+    if b.is_ascii_uppercase() { b + 32 } else { b }
+}
+
+#[inline(always)]
+fn is_vowel_lower(b: u8) -> bool {
+    matches!(b, b'a' | b'e' | b'i' | b'o' | b'u')
+}
+
+#[inline(always)]
+fn is_whitespace(b: u8) -> bool {
+    b == b' ' || b == b'\n' || b == b'\t' || b == b'\r'
+}
+
+#[inline(always)]
+fn is_sentence_end(b: u8) -> bool {
+    matches!(b, b'.' | b'!' | b'?')
+}
+
+/// The first constraint a text failed, reported by `verify_text_detailed`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize))]
+pub enum TextError {
+    /// Outside 256–800 bytes.
+    Length,
+    /// Second half is the byte-reversal of the first.
+    Mirrored,
+    /// Contains a byte > 127.
+    NonAscii,
+    /// A hyphen not joining two letters or digits: leading, trailing or doubled.
+    Hyphen,
+    /// More than `MAX_WHITESPACE_RUN` whitespace bytes in a row.
+    WhitespaceRun,
+    /// Digits make up more than `max_digit_pct` of the bytes.
+    DigitRatio,
+    /// More sentences start lowercase than `lowercase_start_tolerance` allows.
+    LowercaseStart,
+    /// A phrase window occurs more than `max_phrase_repeats` times.
+    RepeatedPhrase,
+    /// A sentence has fewer than 5 or more than 35 words.
+    SentenceLength,
+    /// More than `max_sentences` sentences.
+    TooManySentences,
+    /// The same sentence appears twice.
+    DuplicateSentence,
+    /// Required words missing, out of order, or closer than 40 bytes.
+    MissingRequiredWords,
+    /// `anchor_first_word` is set and the first required word is not in the first sentence.
+    FirstWordLate,
+    /// Fewer than 2 sentences.
+    TooFewSentences,
+    /// No sentence ends with '?'.
+    NoQuestion,
+    /// Fewer distinct sentence endings than `min_ending_variety`.
+    EndingVariety,
+    /// No sentence of ≤10 words.
+    NoShortSentence,
+    /// No sentence of ≥20 words.
+    NoLongSentence,
+    /// Vowels outside the vowel band (30–48% of letters by default, widened by `vowel_grace`).
+    VowelRatio,
+    /// Spaces outside the space band (12–22% of bytes by default).
+    SpaceRatio,
+    /// Average letters per word outside 3.8–7.0.
+    WordLength,
+    /// Consonant run over 5, or average run ≥2.5.
+    ConsonantClusters,
+    /// One of th/he/in/er/an occurs fewer than 2 times.
+    Bigrams,
+    /// One of the/ing/" and"/ion occurs fewer than `min_trigrams` times.
+    Trigrams,
+    /// Fewer than `min_unique_bytes` distinct byte values (28 by default).
+    ByteDiversity,
+    /// A sentence has fewer than `min_sentence_unique_bytes` distinct byte values.
+    SentenceDiversity,
+    /// A required word occurs fewer times than `bytes_per_extra_match` asks of the text's length.
+    RequiredWordRepeats,
+    /// `require_balanced_quotes` is set and the text has an odd number of `"`.
+    UnbalancedQuotes,
+}
+
+/// Verify text meets all natural-language constraints under the default rules.
+///
+/// `required_words`: must appear in order, as whole words, with ≥40 byte gap.
+pub fn verify_text(text: &[u8], required_words: &[&[u8]]) -> bool {
+    verify_text_with_rules(text, required_words, &TextRules::DEFAULT)
+}
+
+/// Verify text under explicit `rules`.
+pub fn verify_text_with_rules(text: &[u8], required_words: &[&[u8]], rules: &TextRules) -> bool {
+    verify_text_detailed(text, required_words, rules).is_ok()
+}
+
+/// Verify text under explicit `rules`, reporting the first failed constraint.
+pub fn verify_text_detailed(text: &[u8], required_words: &[&[u8]], rules: &TextRules) -> Result<(), TextError> {
+    verify_text_extended(text, required_words, rules, &TextRulesExt::BASE)
+}
+
+/// `verify_text_detailed` plus the `ext` rules (on-chain: `MineState::ext_rules`).
+/// Unknown boundary modes act as `BOUNDARY_ALPHA`.
+pub fn verify_text_extended(
+    text: &[u8],
+    required_words: &[&[u8]],
+    rules: &TextRules,
+    ext: &TextRulesExt,
+) -> Result<(), TextError> {
+    let len = text.len();
+    let boundary_mode = ext.boundary_mode;
+    let max_digits = ext.max_digit_pct as usize * len;
+
+    // ── 1. Length: 256–800 bytes ──
+    // (Solana tx limit is 1232 bytes; ~900 usable for text after overhead)
+    if !(MIN_TEXT_LEN..=800).contains(&len) {
+        return Err(TextError::Length);
+    }
+
+    // ── Mirrored (palindromic) structure ──
+    if rules.reject_mirrored && is_mirrored(text) {
+        return Err(TextError::Mirrored);
+    }
+
+    // ── State variables ──
+    let mut digit_count: usize = 0;
+    let mut letter_count: u32 = 0;
+    let mut vowel_count: u32 = 0;
+    let mut space_count: u32 = 0;
+    let mut ws_run: u32 = 0;
+    let mut open_quote: bool = false; // odd number of '"' so far
+
+    // Byte diversity: 256-bit bitmap in 4 × u64, for the text and the current sentence
+    let mut bmap: [u64; 4] = [0; 4];
+    let mut sent_bmap: [u64; 4] = [0; 4];
+
+    // Bigrams (case-insensitive)
+    let mut prev_lower: u8 = 0;
+    let mut bg_th: u32 = 0;
+    let mut bg_he: u32 = 0;
+    let mut bg_in: u32 = 0;
+    let mut bg_er: u32 = 0;
+    let mut bg_an: u32 = 0;
+
+    // Trigrams (rolling lowercase window; " and" needs one extra byte)
+    let mut prev2_lower: u8 = 0;
+    let mut prev3_lower: u8 = 0;
+    let mut tg_the: u32 = 0;
+    let mut tg_ing: u32 = 0;
+    let mut tg_and: u32 = 0;
+    let mut tg_ion: u32 = 0;
+
+    // Consonant clusters
+    let mut cons_run: u32 = 0;
+    let mut cons_total: u32 = 0;
+    let mut cons_count: u32 = 0;
+
+    // Sentence tracking
+    let mut words_in_sent: u32 = 0;
+    let mut in_word: bool = false;
+    let mut word_count: u32 = 0;
+    let mut sent_count: u32 = 0;
+    let mut has_question: bool = false;
+    let mut endings: u8 = 0;           // bit per sentence ending seen: . ! ?
+    let mut awaiting_capital: bool = ext.require_capitals; // before a sentence's first letter
+    let mut lowercase_starts: u8 = 0;
+    let mut has_short: bool = false;   // ≤10 words
+    let mut has_long: bool = false;    // ≥20 words
+    let mut sent_start: usize = 0;
+    let mut sent_started: bool = false;
+
+    // Repeated phrases across the whole text
+    let mut phrases = PhraseTracker::new();
+
+    // Sentence dedup (Bloom filter, no capacity limit)
+    let mut sent_filter = SentenceFilter::new();
+
+    // Vowel band widening for the required words (also used by the early ratio check)
+    let skew = required_vowel_skew(required_words, ext);
+
+    // Required word matching
+    let rw_total = required_words.len();
+    let mut rw_idx: usize = 0;       // which required word we're looking for
+    let mut rw_match: usize = 0;     // bytes matched so far in current word
+    let mut rw_match_start: usize = 0; // where current match started
+    let mut last_rw_end: usize = 0;  // end position of last matched word
+    let mut has_rw_match: bool = false;
+    let mut first_rw_sent: u32 = 0; // sentence index of the first required word's match
+
+    // Required word repeats: whole-word occurrences of each word anywhere,
+    // counted only when the length calls for more than one match
+    let matches = ext.required_matches(len);
+    let mut rep_pos: [u8; MAX_REQUIRED] = [0; MAX_REQUIRED];     // bytes matched so far, per word
+    let mut rep_count: [u16; MAX_REQUIRED] = [0; MAX_REQUIRED];  // whole-word occurrences, per word
+
+    // ── Main loop ──
+    let mut i: usize = 0;
+    while i < len {
+        let b = text[i];
+        let lower = to_lower(b);
+        let alpha = is_alpha(b);
+        let vowel = alpha && is_vowel_lower(lower);
+        let space = b == b' ';
+        let ws = is_whitespace(b);
+        let sent_end = is_sentence_end(b);
+
+        // ASCII only — reject bytes > 127
+        if b > 127 {
+            return Err(TextError::NonAscii);
+        }
+
+        // Whitespace padding: the space ratio must come from gaps between words
+        if ws {
+            ws_run += 1;
+            if ws_run > MAX_WHITESPACE_RUN {
+                return Err(TextError::WhitespaceRun);
+            }
+        } else {
+            ws_run = 0;
+        }
+
+        // Digits only accumulate, so fail as soon as they pass the ceiling
+        if b.is_ascii_digit() {
+            digit_count += 1;
+            if max_digits > 0 && digit_count * 100 > max_digits {
+                return Err(TextError::DigitRatio);
+            }
+        }
+
+        // Sentence-initial capital (first letter after a boundary)
+        if awaiting_capital && alpha {
+            awaiting_capital = false;
+            if b.is_ascii_lowercase() {
+                lowercase_starts += 1;
+                if lowercase_starts > ext.lowercase_start_tolerance {
+                    return Err(TextError::LowercaseStart);
+                }
+            }
+        }
+
+        // Hyphens only join compounds ("well-known"); "--", "word-" and " - " are out.
+        // Like a space, a hyphen still ends a consonant run and a required-word match.
+        if b == b'-' && !hyphen_joins(text, i) {
+            return Err(TextError::Hyphen);
+        }
+
+        if b == b'"' { open_quote = !open_quote; }
+
+        // Byte diversity
+        bmap[(b >> 6) as usize] |= 1u64 << (b & 63);
+        sent_bmap[(b >> 6) as usize] |= 1u64 << (b & 63);
+
+        // Letter / vowel / space counts
+        if alpha {
+            letter_count += 1;
+            if vowel { vowel_count += 1; }
+        }
+        if space { space_count += 1; }
+
+        // Consonant cluster tracking; a run past 5 can only fail, so stop here
+        if alpha && !vowel {
+            cons_run += 1;
+            if cons_run > 5 {
+                return Err(TextError::ConsonantClusters);
+            }
+        } else if cons_run > 0 && !is_apostrophe(b) {
+            cons_total += cons_run;
+            cons_count += 1;
+            cons_run = 0;
+        }
+
+        // Bigram detection
+        if i > 0 {
+            match (prev_lower, lower) {
+                (b't', b'h') => bg_th += 1,
+                (b'h', b'e') => bg_he += 1,
+                (b'i', b'n') => bg_in += 1,
+                (b'e', b'r') => bg_er += 1,
+                (b'a', b'n') => bg_an += 1,
+                _ => {}
+            }
+        }
+
+        // Trigram detection
+        match (prev2_lower, prev_lower, lower) {
+            (b't', b'h', b'e') => tg_the += 1,
+            (b'i', b'n', b'g') => tg_ing += 1,
+            (b'a', b'n', b'd') if prev3_lower == b' ' => tg_and += 1,
+            (b'i', b'o', b'n') => tg_ion += 1,
+            _ => {}
+        }
+        prev3_lower = prev2_lower;
+        prev2_lower = prev_lower;
+        prev_lower = lower;
+
+        // Word tracking within sentence
+        if ws || sent_end {
+            if in_word && !phrases.word_end(text, i, rules.phrase_window, rules.max_phrase_repeats) {
+                return Err(TextError::RepeatedPhrase);
+            }
+            in_word = false;
+        } else if !in_word {
+            in_word = true;
+            words_in_sent += 1;
+            word_count += 1;
+            phrases.word_start(i);
+        }
+
+        // Sentence start position (skip leading whitespace)
+        if !sent_started && !ws && !sent_end {
+            sent_start = i;
+            sent_started = true;
+        }
+
+        // ── Required word matching (with word boundary check) ──
+        if rw_idx < rw_total {
+            let rw = required_words[rw_idx];
+            if !rw.is_empty() && lower == to_lower(rw[rw_match]) {
+                if rw_match == 0 {
+                    rw_match_start = i;
+                }
+                rw_match += 1;
+                if rw_match == rw.len() {
+                    // Full match — check word boundaries. A trailing apostrophe
+                    // is a boundary, so "wonder's" still holds "wonder".
+                    let before_ok = !continues_word_before(text, rw_match_start, boundary_mode);
+                    let after_ok = i + 1 >= len || !joins_word(text[i + 1], boundary_mode);
+
+                    if before_ok && after_ok {
+                        // Check minimum gap from previous match
+                        if has_rw_match && rw_match_start < last_rw_end + 40 {
+                            // Gap too small — skip this occurrence, fall through to reset
+                        } else {
+                            // Each slot consumes its own occurrence: a match starts
+                            // after the previous one ended, so a word required
+                            // twice must appear twice.
+                            debug_assert!(!has_rw_match || rw_match_start >= last_rw_end);
+                            if !has_rw_match { first_rw_sent = sent_count; }
+                            last_rw_end = i + 1;
+                            has_rw_match = true;
+                            rw_idx += 1;
+                        }
+                    }
+                    // Reset and check if current byte starts new match
+                    // (handles both: boundary fail → retry same word,
+                    //  and success → check next word)
+                    rw_match = 0;
+                    if rw_idx < rw_total {
+                        let rw_next = required_words[rw_idx];
+                        if !rw_next.is_empty() && lower == to_lower(rw_next[0]) {
+                            rw_match_start = i;
+                            rw_match = 1;
+                        }
+                    }
+                }
+            } else if rw_match > 0 {
+                // Match interrupted — reset and check if current byte starts new match
+                rw_match = 0;
+                if !rw.is_empty() && lower == to_lower(rw[0]) {
+                    rw_match_start = i;
+                    rw_match = 1;
+                }
+            }
+        }
+
+        // ── Required word repeats (same boundaries, no order or gap rules) ──
+        // Words are letters, so a failed partial match can't hide a start:
+        // no byte after a matched letter begins a word.
+        if matches > 1 {
+            for (k, rw) in required_words.iter().take(MAX_REQUIRED).enumerate() {
+                let pos = rep_pos[k] as usize;
+                let extends = pos < rw.len()
+                    && lower == to_lower(rw[pos])
+                    && (pos > 0 || !continues_word_before(text, i, boundary_mode));
+                rep_pos[k] = if extends { pos as u8 + 1 } else { 0 };
+                if extends && pos + 1 == rw.len() {
+                    if i + 1 >= len || !joins_word(text[i + 1], boundary_mode) {
+                        rep_count[k] += 1;
+                    }
+                    rep_pos[k] = 0;
+                }
+            }
+        }
+
+        // ── Sentence end ──
+        if sent_end && words_in_sent > 0 && sent_started {
+            // Word count bounds: 5–35
+            if !(5..=35).contains(&words_in_sent) {
+                return Err(TextError::SentenceLength);
+            }
+            if b == b'?' { has_question = true; }
+            endings |= match b { b'.' => 1, b'!' => 2, _ => 4 };
+            if words_in_sent <= 10 { has_short = true; }
+            if words_in_sent >= 20 { has_long = true; }
+
+            // Sentence cap (bounds compute)
+            if sent_count >= rules.max_sentences {
+                return Err(TextError::TooManySentences);
+            }
+
+            // Sentence dedup
+            if rules.dedup_sentences && !sent_filter.insert(&text[sent_start..=i]) {
+                return Err(TextError::DuplicateSentence);
+            }
+
+            // Per-sentence diversity: catches one repetitive sentence in otherwise varied text
+            if ext.min_sentence_unique_bytes > 0 && count_unique(&sent_bmap) < ext.min_sentence_unique_bytes as u32 {
+                return Err(TextError::SentenceDiversity);
+            }
+            sent_count += 1;
+
+            // Stop once no ending could bring the ratios back into range
+            let counts = (vowel_count, letter_count, space_count);
+            ratios_reachable(counts, len, len - i - 1, skew, rules.vowel_grace, ext)?;
+
+            // Reset sentence state
+            awaiting_capital = ext.require_capitals;
+            sent_bmap = [0; 4];
+            words_in_sent = 0;
+            in_word = false;
+            sent_started = false;
+        }
+
+        i += 1;
+    }
+
+    // Flush trailing consonant cluster
+    if cons_run > 0 {
+        cons_total += cons_run;
+        cons_count += 1;
+    }
+
+    // ── Post-loop checks ──
+
+    // All required words found
+    if rw_idx < rw_total { return Err(TextError::MissingRequiredWords); }
+    if matches > 1 && !required_words_repeated(required_words, &rep_count, matches) {
+        return Err(TextError::RequiredWordRepeats);
+    }
+    if rules.anchor_first_word && has_rw_match && first_rw_sent > 0 { return Err(TextError::FirstWordLate); }
+
+    // Sentence structure
+    if sent_count < 2 { return Err(TextError::TooFewSentences); }
+    if !has_question { return Err(TextError::NoQuestion); }
+    if endings.count_ones() < ext.min_ending_variety as u32 { return Err(TextError::EndingVariety); }
+    if ext.require_balanced_quotes && open_quote { return Err(TextError::UnbalancedQuotes); }
+    if !has_short { return Err(TextError::NoShortSentence); }
+    if !has_long { return Err(TextError::NoLongSentence); }
+
+    // Vowel ratio: 30–48% of letters (configurable), less whatever the required
+    // words push past the band (capped at `vowel_grace` points)
+    if letter_count == 0 { return Err(TextError::VowelRatio); }
+    let vc = vowel_count as u64;
+    let lc = letter_count as u64;
+    let (deficit, excess) = skew;
+    let cap = rules.vowel_grace as u64 * lc;
+    let (v_lo, v_hi) = (ext.min_vowel_pct as u64, ext.max_vowel_pct as u64);
+    if vc * 100 + deficit.min(cap) < v_lo * lc || vc * 100 > v_hi * lc + excess.min(cap) {
+        return Err(TextError::VowelRatio);
+    }
+
+    // Space ratio: 12–22% of total bytes (configurable)
+    let sc = space_count as u64;
+    let total = len as u64;
+    let (s_lo, s_hi) = (ext.min_space_pct as u64, ext.max_space_pct as u64);
+    if sc * 100 < s_lo * total || sc * 100 > s_hi * total { return Err(TextError::SpaceRatio); }
+
+    // Average word length: 3.8–7.0 letters, against "a to is of" filler and
+    // run-on tokens. The space ratio alone lets comma-padded filler down to ~3.6.
+    let wc = word_count as u64;
+    if lc * 10 < 38 * wc || lc * 10 > 70 * wc { return Err(TextError::WordLength); }
+
+    // Consonant clusters: max ≤5 (checked in the loop), avg <2.5
+    if cons_count > 0 && cons_total * 10 >= 25 * cons_count { return Err(TextError::ConsonantClusters); }
+
+    // Bigrams: th/he/in/er/an each ≥2
+    if bg_th < 2 || bg_he < 2 || bg_in < 2 || bg_er < 2 || bg_an < 2 { return Err(TextError::Bigrams); }
+
+    // Trigrams: the/ing/" and"/ion each ≥ min_trigrams
+    let mt = rules.min_trigrams;
+    if tg_the < mt || tg_ing < mt || tg_and < mt || tg_ion < mt { return Err(TextError::Trigrams); }
+
+    // Byte diversity: ≥28 unique values by default
+    // (natural English text has ~31-34: 22-25 lowercase + 3-5 uppercase + 4-6 punctuation)
+    if count_unique(&bmap) < ext.min_unique_bytes as u32 { return Err(TextError::ByteDiversity); }
+
+    Ok(())
+}
+
+/// Whether each required word occurred, as a whole word, `matches` times for
+/// every slot it fills in `required_words`. `counts` holds the main loop's
+/// occurrences per word (the first `MAX_REQUIRED` words), counted anywhere,
+/// without the in-order and 40-byte-gap rules the first match follows.
+fn required_words_repeated(required_words: &[&[u8]], counts: &[u16], matches: usize) -> bool {
+    required_words.iter().zip(counts).all(|(word, &count)| {
+        let slots = required_words.iter().filter(|w| w.eq_ignore_ascii_case(word)).count();
+        count as usize >= slots * matches
+    })
+}
+
+/// Distinct byte values set in a 256-bit byte bitmap.
+#[inline(always)]
+fn count_unique(bmap: &[u64; 4]) -> u32 {
+    bmap[0].count_ones() + bmap[1].count_ones() + bmap[2].count_ones() + bmap[3].count_ones()
+}
+
+/// Fail early if the vowel or space ratio can no longer pass, whatever the
+/// `remaining` bytes turn out to be. Conservative: each bound is necessary for
+/// the post-loop check (the vowel grace is taken at its largest, and vowels and
+/// spaces are judged independently though they compete for the same bytes), so
+/// a text that could still pass is never cut short, but one that can't may run
+/// on to the end. Failing texts typically stop about 10% early.
+fn ratios_reachable(
+    (vowels, letters, spaces): (u32, u32, u32),
+    len: usize,
+    remaining: usize,
+    (deficit, excess): (u64, u64),
+    vowel_grace: u8,
+    ext: &TextRulesExt,
+) -> Result<(), TextError> {
+    let (vc, lc, sc) = (vowels as u64, letters as u64, spaces as u64);
+    let (total, rem) = (len as u64, remaining as u64);
+
+    // Spaces only accumulate: already over the ceiling, or under the floor even
+    // if every remaining byte is a space
+    if sc * 100 > ext.max_space_pct as u64 * total || (sc + rem) * 100 < ext.min_space_pct as u64 * total {
+        return Err(TextError::SpaceRatio);
+    }
+
+    // Vowels: the best ending for the floor is all vowels, for the ceiling
+    // all consonants
+    let grace = vowel_grace as u64;
+    let most = lc + rem;
+    if (vc + rem) * 100 + deficit.min(grace * most) < ext.min_vowel_pct as u64 * most
+        || vc * 100 > ext.max_vowel_pct as u64 * most + excess.min(grace * most)
+    {
+        return Err(TextError::VowelRatio);
+    }
+    Ok(())
+}
+
+/// How far the required words alone sit outside `ext`'s vowel band, in
+/// hundredths of a letter: `(below the floor, above the ceiling)`. Net over all
+/// words, so a vowel-rich word offsets a vowel-poor one.
+fn required_vowel_skew(required_words: &[&[u8]], ext: &TextRulesExt) -> (u64, u64) {
+    let mut letters: u64 = 0;
+    let mut vowels: u64 = 0;
+    for w in required_words {
+        for &b in w.iter() {
+            if is_alpha(b) {
+                letters += 1;
+                if is_vowel_lower(to_lower(b)) { vowels += 1; }
+            }
+        }
+    }
+    let (lo, hi) = (ext.min_vowel_pct as u64, ext.max_vowel_pct as u64);
+    ((lo * letters).saturating_sub(100 * vowels), (100 * vowels).saturating_sub(hi * letters))
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn natural_text() -> Vec<u8> {
+        let text = "The weather in the morning was rather interesting and \
+            pleasant for an early spring day in the northern hemisphere. \
+            Have you ever wondered whether the inner workings of nature \
+            can truly be understood through simple observation and careful \
+            thinking about the patterns that emerge in everything around us? \
+            The ancient trees in the garden were standing tall and their \
+            branches reached toward the bright sky above. \
+            The morning air felt crisp and fresh. \
+            Another interesting thing happened when the river began to \
+            change direction and the water flowed in an entirely different \
+            manner than before. \
+            Is there anything more beautiful than a quiet evening spent \
+            reading by the fireplace?";
+        text.as_bytes().to_vec()
+    }
+
+    #[test]
+    fn test_natural_passes() {
+        let text = natural_text();
+        let words: &[&[u8]] = &[b"weather", b"nature", b"ancient"];
+        assert!(verify_text(&text, words), "Natural text should pass, len={}", text.len());
+    }
+
+    #[test]
+    fn test_contractions() {
+        let text = b"It's a quiet morning and we're sitting by the window, watching the \
+            weather change over the hills while the kettle's singing in the kitchen. \
+            Don't you think there's something wonderful about a day that doesn't ask \
+            anything of us, where nobody's in a hurry and the world's wonder can't \
+            be missed? They'll say we shouldn't waste time like this. \
+            I'd rather listen to the birds than to anyone's advice about ambition, \
+            and I'm sure you'll agree that it isn't a waste at all. \
+            We've learned that o'clock means nothing on a morning like this one.";
+        let rules = TextRules::DEFAULT;
+        assert_eq!(verify_text_detailed(text, &[b"weather", b"wonder"], &rules), Ok(()));
+        // "wonder's" style suffixes keep the boundary; "o'clock" doesn't hold "clock"
+        assert_eq!(verify_text_detailed(text, &[b"world"], &rules), Ok(()));
+        assert_eq!(verify_text_detailed(text, &[b"clock"], &rules), Err(TextError::MissingRequiredWords));
+        // A leading quote mark is still a boundary
+        assert!(!continues_word_before(b"say 'wonder'", 5, BOUNDARY_ALPHA));
+        assert!(continues_word_before(b"o'clock", 2, BOUNDARY_ALPHA));
+
+        // The apostrophe doesn't split a consonant run: "borscht's" is 6 in a row
+        let t = String::from_utf8(text.to_vec()).unwrap().replace("kettle's", "borscht's");
+        assert_eq!(verify_text_detailed(t.as_bytes(), &[], &rules), Err(TextError::ConsonantClusters));
+    }
+
+    #[test]
+    fn test_hyphenated_compounds() {
+        let base = String::from_utf8(natural_text()).unwrap();
+        let rules = TextRules::DEFAULT;
+        let text = base
+            .replace("The ancient trees", "The well-known ancient trees")
+            .replace("The morning air felt", "The self-aware morning air felt");
+        // Each compound is one word, and its parts still match required words
+        assert_eq!(verify_text_detailed(text.as_bytes(), &[b"weather", b"well", b"aware"], &rules), Ok(()));
+        let dated = base.replace("The morning air felt", "The 2024-2025 morning air felt");
+        assert_eq!(verify_text_detailed(dated.as_bytes(), &[], &rules), Ok(()));
+
+        // Hyphen spam: doubled, dangling, or standing alone
+        for spam in ["The ancient--trees", "The ancient- trees", "The -ancient trees", "The ancient - trees"] {
+            let t = base.replace("The ancient trees", spam);
+            assert_eq!(verify_text_detailed(t.as_bytes(), &[], &rules), Err(TextError::Hyphen), "{spam}");
+        }
+        let trailing = format!("{}-", base.trim_end());
+        assert_eq!(verify_text_detailed(trailing.as_bytes(), &[], &rules), Err(TextError::Hyphen));
+    }
+
+    #[test]
+    fn test_digit_ratio() {
+        let rules = TextRules::DEFAULT;
+        let ext = TextRulesExt::DEFAULT;
+        let base = String::from_utf8(natural_text()).unwrap();
+        assert_eq!(verify_text_extended(base.as_bytes(), &[], &rules, &ext), Ok(()));
+
+        // A short text padded out to 256 bytes with a digit block
+        let short = "The weather in the morning was pleasant and everyone spoke about it. \
+            Have you ever wondered whether careful thinking explains the world around us? \
+            The morning air felt crisp. Reference ";
+        let padded = format!("{short}{}.", "1234567890".repeat(12))[..256].to_string();
+        assert_eq!(verify_text_extended(padded.as_bytes(), &[], &rules, &ext), Err(TextError::DigitRatio));
+        // Without the ceiling it gets past the digit check to a later one
+        assert_ne!(verify_text_detailed(padded.as_bytes(), &[], &rules), Err(TextError::DigitRatio));
+
+        // A few numbers stay well under 5%
+        let dated = base.replace("The ancient trees", "In 1987 the ancient trees");
+        assert_eq!(verify_text_extended(dated.as_bytes(), &[], &rules, &ext), Ok(()));
+        // and a higher ceiling admits number-heavy text
+        let heavy = base.replace("The ancient trees", "The 12345678901234567890 12345678901234567890 ancient trees");
+        assert_eq!(verify_text_extended(heavy.as_bytes(), &[], &rules, &ext), Err(TextError::DigitRatio));
+        let lax = TextRulesExt { max_digit_pct: 10, ..ext };
+        assert_eq!(verify_text_extended(heavy.as_bytes(), &[], &rules, &lax), Ok(()));
+        assert!(!TextRulesExt { max_digit_pct: 101, ..ext }.is_valid());
+    }
+
+    #[test]
+    fn test_custom_ratio_bounds() {
+        let rules = TextRules::DEFAULT;
+        let text = natural_text(); // ~36% vowels, 16% spaces
+        let ext = TextRulesExt::DEFAULT;
+        assert_eq!(verify_text_extended(&text, &[], &rules, &ext), Ok(()));
+
+        // Tightened past the text's ratios
+        let vowels = TextRulesExt { min_vowel_pct: 40, ..ext };
+        assert_eq!(verify_text_extended(&text, &[], &rules, &vowels), Err(TextError::VowelRatio));
+        let spaces = TextRulesExt { max_space_pct: 15, ..ext };
+        assert_eq!(verify_text_extended(&text, &[], &rules, &spaces), Err(TextError::SpaceRatio));
+        // Loosened bands still pass it
+        let wide = TextRulesExt { min_vowel_pct: 20, max_vowel_pct: 60, min_space_pct: 5, max_space_pct: 30, ..ext };
+        assert_eq!(verify_text_extended(&text, &[], &rules, &wide), Ok(()));
+
+        // Bands must be low < high <= 100
+        assert!(wide.is_valid());
+        assert!(!TextRulesExt { min_vowel_pct: 48, ..ext }.is_valid());
+        assert!(!TextRulesExt { max_space_pct: 101, ..ext }.is_valid());
+        assert!(!TextRulesExt { min_space_pct: 0, max_space_pct: 0, ..ext }.is_valid());
+    }
+
+    #[test]
+    fn test_sentence_diversity() {
+        let rules = TextRules::DEFAULT;
+        let text = String::from_utf8(natural_text()).unwrap(); // 31 distinct bytes, ≥19 per sentence
+        let ext = TextRulesExt { min_sentence_unique_bytes: 16, ..TextRulesExt::DEFAULT };
+        assert_eq!(verify_text_extended(text.as_bytes(), &[], &rules, &ext), Ok(()));
+
+        // One locally repetitive sentence: still 31 distinct bytes overall
+        let short = "The morning air felt crisp and fresh.";
+        assert!(text.contains(short));
+        let local = text.replace(short, "Tea at ten, then tea at one.");
+        assert_eq!(verify_text_extended(local.as_bytes(), &[], &rules, &TextRulesExt::DEFAULT), Ok(()));
+        assert_eq!(verify_text_extended(local.as_bytes(), &[], &rules, &ext), Err(TextError::SentenceDiversity));
+
+        // The global floor is configurable too
+        let global = TextRulesExt { min_unique_bytes: 32, ..TextRulesExt::DEFAULT };
+        assert_eq!(verify_text_extended(text.as_bytes(), &[], &rules, &global), Err(TextError::ByteDiversity));
+
+        // 1 <= global <= 128, and the sentence floor may not exceed it
+        assert!(ext.is_valid());
+        assert!(!TextRulesExt { min_unique_bytes: 0, ..ext }.is_valid());
+        assert!(!TextRulesExt { min_unique_bytes: 129, ..ext }.is_valid());
+        assert!(!TextRulesExt { min_sentence_unique_bytes: 29, ..ext }.is_valid());
+    }
+
+    #[test]
+    fn test_required_word_repeats() {
+        let rules = TextRules::DEFAULT;
+        let text = natural_text(); // 675 bytes, each of these words once
+        let words: &[&[u8]] = &[b"weather", b"nature", b"ancient"];
+        let ext = TextRulesExt { bytes_per_extra_match: 400, ..TextRulesExt::DEFAULT };
+        assert_eq!(verify_text_extended(&text, words, &rules, &TextRulesExt::DEFAULT), Ok(()));
+
+        // 419 bytes past the minimum: every word is needed twice
+        assert_eq!(ext.required_matches(text.len()), 2);
+        assert_eq!(verify_text_extended(&text, words, &rules, &ext), Err(TextError::RequiredWordRepeats));
+        // Words the text does repeat pass, wherever the extra matches fall
+        let common: &[&[u8]] = &[b"morning", b"interesting"];
+        assert_eq!(verify_text_extended(&text, common, &rules, &ext), Ok(()));
+        // Only whole words count: "thing" once on its own, twice more inside
+        // "everything" and "anything", is one short
+        assert_eq!(verify_text_extended(&text, &[b"thing"], &rules, &ext), Err(TextError::RequiredWordRepeats));
+        assert_eq!(verify_text_extended(&text, &[b"the"], &rules, &ext), Ok(()));
+        // A coarser step leaves this length at one match
+        let coarse = TextRulesExt { bytes_per_extra_match: 500, ..ext };
+        assert_eq!(verify_text_extended(&text, words, &rules, &coarse), Ok(()));
+        assert_eq!(ext.required_matches(MIN_TEXT_LEN + 399), 1);
+        assert_eq!(ext.required_matches(800), 2);
+
+        assert!(ext.is_valid());
+        assert!(!TextRulesExt { bytes_per_extra_match: MIN_BYTES_PER_EXTRA_MATCH - 1, ..ext }.is_valid());
+    }
+
+    #[test]
+    fn test_balanced_quotes() {
+        let rules = TextRules::DEFAULT;
+        let text = String::from_utf8(natural_text()).unwrap();
+        let ext = TextRulesExt { require_balanced_quotes: true, ..TextRulesExt::DEFAULT };
+        let short = "The morning air felt crisp and fresh.";
+        assert!(text.contains(short));
+
+        let quoted = text.replace(short, "The morning air felt \"crisp\" and fresh.");
+        assert_eq!(verify_text_extended(quoted.as_bytes(), &[], &rules, &ext), Ok(()));
+        // A dangling quote fails only under the flag
+        let dangling = text.replace(short, "The morning air felt \"crisp and fresh.");
+        assert_eq!(verify_text_extended(dangling.as_bytes(), &[], &rules, &ext), Err(TextError::UnbalancedQuotes));
+        assert_eq!(verify_text_extended(dangling.as_bytes(), &[], &rules, &TextRulesExt::DEFAULT), Ok(()));
+        // No quotes at all is balanced
+        assert_eq!(verify_text_extended(text.as_bytes(), &[], &rules, &ext), Ok(()));
+    }
+
+    #[test]
+    fn test_word_length() {
+        let rules = TextRules::DEFAULT;
+        let text = String::from_utf8(natural_text()).unwrap();
+        assert_eq!(verify_text_detailed(text.as_bytes(), &[], &rules), Ok(()));
+
+        // One- and two-letter filler, with commas keeping the space ratio in range
+        let long = "Another interesting thing happened when the river began to \
+            change direction and the water flowed in an entirely different \
+            manner than before.";
+        let trees = "The ancient trees in the garden were standing tall and their \
+            branches reached toward the bright sky above.";
+        assert!(text.contains(long) && text.contains(trees));
+        let filler = text
+            .replace(trees, "So, as it is, we go on to it, as we do, and so on, if it is so, \
+                or if we go up to it, as is our way, in it.")
+            .replace(long, "We go, you go, he is up, she is on, it is so, I am in, \
+                do as we do, be as we be, so it is, at an end.");
+        assert_eq!(verify_text_detailed(filler.as_bytes(), &[], &rules), Err(TextError::WordLength));
+    }
+
+    #[test]
+    fn test_whitespace_runs() {
+        let rules = TextRules::DEFAULT;
+        let text = String::from_utf8(natural_text()).unwrap();
+        assert_eq!(verify_text_detailed(text.as_bytes(), &[], &rules), Ok(()));
+
+        // Two spaces after a full stop and a paragraph break are fine
+        let spaced = text.replace("northern hemisphere. ", "northern hemisphere.  ")
+            .replace("sky above. ", "sky above.\n\n");
+        assert_eq!(verify_text_detailed(spaced.as_bytes(), &[], &rules), Ok(()));
+
+        // Padding between words fails, whatever the whitespace byte
+        for pad in ["   ", " \t ", "\n\n\n", "\r\n "] {
+            let padded = text.replace("rather interesting", &format!("rather{pad}interesting"));
+            assert_eq!(verify_text_detailed(padded.as_bytes(), &[], &rules), Err(TextError::WhitespaceRun), "{pad:?}");
+        }
+    }
+
+    #[test]
+    fn test_sentence_capitals() {
+        let rules = TextRules::DEFAULT;
+        let ext = TextRulesExt::DEFAULT;
+        let text = String::from_utf8(natural_text()).unwrap();
+        assert_eq!(verify_text_extended(text.as_bytes(), &[], &rules, &ext), Ok(()));
+
+        // One lowercase start is tolerated, two are not
+        let one = text.replace("The morning air felt", "the morning air felt");
+        assert_eq!(verify_text_extended(one.as_bytes(), &[], &rules, &ext), Ok(()));
+        let two = one.replace("Another interesting thing", "another interesting thing");
+        assert_eq!(verify_text_extended(two.as_bytes(), &[], &rules, &ext), Err(TextError::LowercaseStart));
+        let strict = TextRulesExt { lowercase_start_tolerance: 0, ..ext };
+        assert_eq!(verify_text_extended(one.as_bytes(), &[], &rules, &strict), Err(TextError::LowercaseStart));
+
+        // All lowercase fails; the first letter counts, not a leading digit or quote
+        let lower = text.to_ascii_lowercase();
+        assert_eq!(verify_text_extended(lower.as_bytes(), &[], &rules, &ext), Err(TextError::LowercaseStart));
+        let quoted = text.replace("The morning air felt", "'The morning air felt");
+        assert_eq!(verify_text_extended(quoted.as_bytes(), &[], &rules, &strict), Ok(()));
+        // Off: lowercase starts pass as before
+        assert_eq!(verify_text_detailed(two.as_bytes(), &[], &rules), Ok(()));
+
+        assert!(TextRulesExt { lowercase_start_tolerance: MAX_LOWERCASE_START_TOLERANCE, ..ext }.is_valid());
+        assert!(!TextRulesExt { lowercase_start_tolerance: MAX_LOWERCASE_START_TOLERANCE + 1, ..ext }.is_valid());
+    }
+
+    #[test]
+    fn test_ending_variety() {
+        let rules = TextRules::DEFAULT;
+        let two = TextRulesExt { min_ending_variety: 2, ..TextRulesExt::BASE };
+        // Periods and questions: two kinds
+        let text = natural_text();
+        assert_eq!(verify_text_extended(&text, &[], &rules, &two), Ok(()));
+        let three = TextRulesExt { min_ending_variety: 3, ..two };
+        assert_eq!(verify_text_extended(&text, &[], &rules, &three), Err(TextError::EndingVariety));
+        let exclaimed = String::from_utf8(text.clone()).unwrap().replace("crisp and fresh.", "crisp and fresh!");
+        assert_eq!(verify_text_extended(exclaimed.as_bytes(), &[], &rules, &three), Ok(()));
+
+        // One kind only: all questions fails variety; all periods already lacks the question
+        let asked = String::from_utf8(text.clone()).unwrap().replace('.', "?");
+        assert_eq!(verify_text_extended(asked.as_bytes(), &[], &rules, &two), Err(TextError::EndingVariety));
+        assert_eq!(verify_text_extended(asked.as_bytes(), &[], &rules, &TextRulesExt::BASE), Ok(()));
+        let flat = String::from_utf8(text).unwrap().replace('?', ".");
+        assert_eq!(verify_text_extended(flat.as_bytes(), &[], &rules, &two), Err(TextError::NoQuestion));
+        assert!(!TextRulesExt { min_ending_variety: 4, ..two }.is_valid());
+    }
+
+    #[test]
+    fn test_boundary_modes() {
+        let base = String::from_utf8(natural_text()).unwrap();
+        let rules = TextRules::DEFAULT;
+        let check = |text: &str, mode: u8| {
+            let ext = TextRulesExt { boundary_mode: mode, ..TextRulesExt::BASE };
+            verify_text_extended(text.as_bytes(), &[b"garden"], &rules, &ext)
+        };
+        assert!(base.contains("the garden were"));
+
+        // Standalone: every mode
+        for mode in [BOUNDARY_ALPHA, BOUNDARY_ALPHANUMERIC, BOUNDARY_WHITESPACE] {
+            assert_eq!(check(&base, mode), Ok(()));
+        }
+
+        // Next to a digit: only letters join in the alpha mode
+        let digit = base.replace("the garden were", "the garden2 were");
+        assert_eq!(check(&digit, BOUNDARY_ALPHA), Ok(()));
+        assert_eq!(check(&digit, BOUNDARY_ALPHANUMERIC), Err(TextError::MissingRequiredWords));
+        assert_eq!(check(&digit, BOUNDARY_WHITESPACE), Err(TextError::MissingRequiredWords));
+
+        // Next to punctuation: a boundary except in the whitespace mode
+        let comma = base.replace("the garden were", "the garden, were");
+        assert_eq!(check(&comma, BOUNDARY_ALPHA), Ok(()));
+        assert_eq!(check(&comma, BOUNDARY_ALPHANUMERIC), Ok(()));
+        assert_eq!(check(&comma, BOUNDARY_WHITESPACE), Err(TextError::MissingRequiredWords));
+
+        // Inside a longer word: no mode matches
+        let longer = base.replace("the garden were", "the gardener was");
+        for mode in [BOUNDARY_ALPHA, BOUNDARY_ALPHANUMERIC, BOUNDARY_WHITESPACE] {
+            assert_eq!(check(&longer, mode), Err(TextError::MissingRequiredWords));
+        }
+
+        assert_eq!(
+            verify_text_detailed(digit.as_bytes(), &[b"garden"], &rules),
+            check(&digit, BOUNDARY_ALPHA)
+        );
+        assert!(is_valid_boundary_mode(BOUNDARY_WHITESPACE));
+        assert!(!is_valid_boundary_mode(3));
+    }
+
+    #[test]
+    fn test_too_short() {
+        assert!(!verify_text(b"Hello world.", &[]));
+    }
+
+    #[test]
+    fn test_fail_fast() {
+        let text = natural_text();
+        let rules = TextRules::DEFAULT;
+        let base = TextRulesExt::BASE;
+        let skew = required_vowel_skew(&[b"weather"], &base);
+
+        // No prefix of a valid text is cut short by the early ratio check
+        let (mut vc, mut lc, mut sc) = (0, 0, 0);
+        for (i, &b) in text.iter().enumerate() {
+            if is_alpha(b) {
+                lc += 1;
+                if is_vowel_lower(to_lower(b)) { vc += 1; }
+            }
+            if b == b' ' { sc += 1; }
+            assert_eq!(ratios_reachable((vc, lc, sc), text.len(), text.len() - i - 1, skew, rules.vowel_grace, &base), Ok(()));
+        }
+
+        // Out of reach: spaces already past 22% of 400 bytes, or too few left to reach 12%
+        assert_eq!(ratios_reachable((60, 150, 90), 400, 200, (0, 0), 0, &base), Err(TextError::SpaceRatio));
+        assert_eq!(ratios_reachable((60, 150, 10), 400, 30, (0, 0), 0, &base), Err(TextError::SpaceRatio));
+        // Vowels: 10 of 200 letters with 20 bytes left can't reach 30%; 150 of 200 can't drop to 48%
+        assert_eq!(ratios_reachable((10, 200, 60), 400, 20, (0, 0), 0, &base), Err(TextError::VowelRatio));
+        assert_eq!(ratios_reachable((150, 200, 60), 400, 20, (0, 0), 0, &base), Err(TextError::VowelRatio));
+        // ...but enough remaining bytes could still fix either
+        assert_eq!(ratios_reachable((10, 200, 60), 400, 150, (0, 0), 0, &base), Ok(()));
+        assert_eq!(ratios_reachable((150, 200, 60), 400, 150, (0, 0), 0, &base), Ok(()));
+
+        // A 6-consonant run fails at once, ahead of the missing required word
+        let mut bad = text.clone();
+        bad[4..11].copy_from_slice(b"wxyzqrs");
+        assert_eq!(verify_text_detailed(&bad, &[b"absent"], &rules), Err(TextError::ConsonantClusters));
+    }
+
+    #[test]
+    fn test_fail_fast_ratio_exit() {
+        let rules = TextRules::DEFAULT;
+        let base = TextRulesExt::BASE;
+        // Where the early ratio check first gives up on `text`: the sentence end
+        // the main loop returns from, replayed without instrumenting it
+        let exit = |text: &[u8]| {
+            let (mut vc, mut lc, mut sc) = (0, 0, 0);
+            text.iter().enumerate().position(|(i, &b)| {
+                if is_alpha(b) {
+                    lc += 1;
+                    if is_vowel_lower(to_lower(b)) { vc += 1; }
+                }
+                if b == b' ' { sc += 1; }
+                is_sentence_end(b)
+                    && ratios_reachable((vc, lc, sc), text.len(), text.len() - i - 1, (0, 0), rules.vowel_grace, &base).is_err()
+            })
+        };
+
+        // A passing text runs to the end, as before the early check
+        let text = natural_text();
+        assert_eq!(verify_text_detailed(&text, &[], &rules), Ok(()));
+        assert_eq!(exit(&text), None);
+
+        // Too many spaces (26% against the 22% ceiling): out of reach before the last sentence
+        let spacey = b"So he and I went up to the old inn by the sea to see if it was in use. \
+            He had a map of the area and we set off at six in the day as the air was cool and \
+            the sun was low on the hill. Then we sat in the sun by the end of the pier and ate \
+            an egg and a bun. Do you see how we got on? It was a fun day and in the end we got \
+            home by ten at the end of an odd day out on the sea wall. Her cat was on the mat as we got in.";
+        assert_eq!(verify_text_detailed(spacey, &[], &rules), Err(TextError::SpaceRatio));
+        assert!(exit(spacey).is_some_and(|i| i < spacey.len() - 1));
+
+        // Too few vowels (17% of letters): the rest can't lift them to 30% well before the end
+        let dry = b"The strong dry winds swept by the rocky cliffs and the crypt at night. \
+            Shy lynx and fly moths crept by thick dry shrubs in the hush of the spry wind, the gym, \
+            the myths and the beat of the stylish hymn. Why try the dry huts? The thrifty smith \
+            sang his strong hymns and then he went back by the trysting spot with lynx pelts and \
+            dry twigs. Thy sly hymns bring myths in runes.";
+        assert_eq!(verify_text_detailed(dry, &[], &rules), Err(TextError::VowelRatio));
+        assert!(exit(dry).is_some_and(|i| i < dry.len() - 1));
+    }
+
+    #[test]
+    fn test_detailed_errors() {
+        let rules = TextRules::DEFAULT;
+        let text = natural_text();
+        assert_eq!(verify_text_detailed(&text, &[b"weather"], &rules), Ok(()));
+        assert_eq!(verify_text_detailed(b"Too short.", &[], &rules), Err(TextError::Length));
+        assert_eq!(
+            verify_text_detailed(&text, &[b"ancient", b"weather"], &rules),
+            Err(TextError::MissingRequiredWords)
+        );
+        let no_q = String::from_utf8(text).unwrap().replace('?', ".");
+        assert_eq!(verify_text_detailed(no_q.as_bytes(), &[], &rules), Err(TextError::NoQuestion));
+    }
+
+    #[test]
+    fn test_duplicate_sentences() {
+        let s1 = "The weather in the morning was rather interesting and pleasant. ";
+        let q = "Have you ever wondered whether the inner workings of nature can truly be understood? ";
+        let long = "The ancient trees in the garden were standing tall and their branches reached toward the bright sky creating an interesting pattern. ";
+        let mut t = String::new();
+        t.push_str(s1); t.push_str(q); t.push_str(long); t.push_str(s1); // dup!
+        while t.len() < 256 { t.push_str("Another filler sentence in the text here today. "); }
+        assert!(!verify_text(t.as_bytes(), &[]), "Duplicate sentences should fail");
+    }
+
+    #[test]
+    fn test_dedup_toggle() {
+        let mut t = String::from_utf8(natural_text()).unwrap();
+        t.push_str(" The morning air felt crisp and fresh.");
+        let mut rules = TextRules::DEFAULT;
+        assert_eq!(verify_text_detailed(t.as_bytes(), &[], &rules), Err(TextError::DuplicateSentence));
+        rules.dedup_sentences = false;
+        assert_eq!(verify_text_detailed(t.as_bytes(), &[], &rules), Ok(()));
+    }
+
+    #[test]
+    fn test_dedup_beyond_fifty_sentences() {
+        let mut filter = SentenceFilter::new();
+        let mut sentences = Vec::new();
+        for i in 0..70 {
+            sentences.push(format!("Sentence number {} walks along the quiet river.", i));
+        }
+        for s in sentences.iter() {
+            assert!(filter.insert(s.as_bytes()), "distinct sentence flagged: {}", s);
+        }
+        // Duplicate of an early sentence appears at position 71
+        assert!(!filter.insert(sentences[3].as_bytes()), "late duplicate must be caught");
+        assert!(!filter.insert(sentences[69].as_bytes()));
+    }
+
+    #[test]
+    fn test_no_question() {
+        let t = "The weather in the morning was rather interesting and pleasant for an early spring day. \
+            The ancient trees in the garden were standing tall and their branches reached toward the bright sky above and beyond the hills. \
+            Another interesting thing happened when the river began to change direction and the water flowed in an entirely different manner than before. \
+            The evening settled over the land.";
+        let padded = format!("{} {}", t, "More filler text about the interesting weather and the ancient garden path. ".repeat(2));
+        assert!(!verify_text(padded.as_bytes(), &[]), "Missing question should fail");
+    }
+
+    #[test]
+    fn test_missing_required_word() {
+        let text = natural_text();
+        let words: &[&[u8]] = &[b"weather", b"blockchain", b"ancient"];
+        assert!(!verify_text(&text, words), "Missing required word should fail");
+    }
+
+    #[test]
+    fn test_wrong_word_order() {
+        let text = natural_text();
+        let words: &[&[u8]] = &[b"ancient", b"nature"];
+        assert!(!verify_text(&text, words), "Wrong word order should fail");
+    }
+
+    #[test]
+    fn test_repeated_required_word() {
+        let text = natural_text();
+        // "morning" appears twice, far enough apart to fill two slots
+        assert!(verify_text(&text, &[b"morning", b"morning"]));
+        // "ancient" appears once: one occurrence cannot satisfy two slots
+        assert!(!verify_text(&text, &[b"ancient", b"ancient"]));
+        assert!(!verify_text(&text, &[b"morning", b"morning", b"morning"]));
+    }
+
+    #[test]
+    fn test_gap_skip_then_match() {
+        let text = natural_text();
+        // "morning" first appears 8 bytes after "weather" (too close) and again
+        // ~400 bytes later: the later occurrence fills the slot.
+        assert!(verify_text(&text, &[b"weather", b"morning"]));
+        // The too-close occurrence must not advance the matcher, so only one
+        // "morning" slot can be filled after "weather".
+        assert!(!verify_text(&text, &[b"weather", b"morning", b"morning"]));
+        // Without the leading "weather" both occurrences count.
+        assert!(verify_text(&text, &[b"morning", b"morning"]));
+        // A too-close occurrence with no later one leaves the slot unfilled.
+        let t = String::from_utf8(text).unwrap().replace("The morning air", "The cool air");
+        assert_eq!(
+            verify_text_detailed(t.as_bytes(), &[b"weather", b"morning"], &TextRules::DEFAULT),
+            Err(TextError::MissingRequiredWords)
+        );
+    }
+
+    #[test]
+    fn test_vowel_grace() {
+        // ~31% vowels without the four vowel-free required words, ~29% with them
+        let t = "The old crypt stood in the northern hills, wrapped in thick fog and strong \
+            winds that night. Have you ever heard the story of the nymph who sang by the \
+            fresh brook in spring? Carved on the stone was a single glyph. Scholars studying \
+            the strange mark thought that it might show the lymph of the world, a thing which \
+            flows through every living form and brings the breath of motion to all.";
+        let words: &[&[u8]] = &[b"crypt", b"nymph", b"glyph", b"lymph"];
+        assert_eq!(required_vowel_skew(words, &TextRulesExt::BASE), (600, 0));
+        assert_eq!(verify_text_detailed(t.as_bytes(), words, &TextRules::DEFAULT), Ok(()));
+        let mut rules = TextRules::DEFAULT;
+        rules.vowel_grace = 0;
+        assert_eq!(verify_text_detailed(t.as_bytes(), words, &rules), Err(TextError::VowelRatio));
+        // The grace comes from the required words, not the text: without them it doesn't apply
+        assert_eq!(verify_text_detailed(t.as_bytes(), &[], &TextRules::DEFAULT), Err(TextError::VowelRatio));
+        // Balanced words need no grace
+        assert_eq!(required_vowel_skew(&[b"weather", b"nature"], &TextRulesExt::BASE), (0, 0));
+        assert_eq!(required_vowel_skew(&[b"audio"], &TextRulesExt::BASE), (0, 160));
+    }
+
+    #[test]
+    fn test_anchor_first_word() {
+        let text = natural_text();
+        let mut rules = TextRules::DEFAULT;
+        rules.anchor_first_word = true;
+        // "weather" opens the first sentence; "nature" only appears in the second
+        assert_eq!(verify_text_detailed(&text, &[b"weather", b"nature"], &rules), Ok(()));
+        assert_eq!(verify_text_detailed(&text, &[b"nature"], &rules), Err(TextError::FirstWordLate));
+        assert_eq!(verify_text_detailed(&text, &[b"nature"], &TextRules::DEFAULT), Ok(()));
+        // Only the first word is anchored; no required words, nothing to anchor
+        assert_eq!(verify_text_detailed(&text, &[b"morning", b"ancient"], &rules), Ok(()));
+        assert_eq!(verify_text_detailed(&text, &[], &rules), Ok(()));
+    }
+
+    #[test]
+    fn test_word_boundary() {
+        // "other" contains "the" but should NOT match required word "the"
+        // when checking word boundaries
+        let t = "Another other thing happened in the morning when the weather changed and everything \
+            looked rather different from what the ancient stories had described in their pages. \
+            Is there anything more interesting than discovering the hidden patterns in nature? \
+            The answer to this question remains unclear even today. \
+            Sometimes the world reveals its secrets only to those who listen carefully and patiently.";
+        let padded = format!("{} {}", t, "The garden path led through the forest and over the bridge. ");
+        // "other" appears before standalone "the" — should still find standalone "the"
+        let words: &[&[u8]] = &[b"the"];
+        // This should pass because standalone "the" exists
+        if padded.len() >= 256 {
+            assert!(verify_text(padded.as_bytes(), words), "Word boundary: standalone 'the' should match");
+        }
+    }
+
+    #[test]
+    fn test_sentence_cap() {
+        let text = natural_text();
+        let words: &[&[u8]] = &[b"weather", b"nature", b"ancient"];
+        // natural_text has 6 sentences
+        let mut rules = TextRules::DEFAULT;
+        rules.max_sentences = 6;
+        assert!(verify_text_with_rules(&text, words, &rules), "6 sentences within cap of 6");
+        rules.max_sentences = 5;
+        assert!(!verify_text_with_rules(&text, words, &rules), "6 sentences over cap of 5");
+    }
+
+    #[test]
+    fn test_text_rules_bounds() {
+        let mut rules = TextRules::DEFAULT;
+        assert!(rules.is_valid());
+        rules.max_sentences = MAX_SENTENCE_CAP + 1;
+        assert!(!rules.is_valid());
+        rules.max_sentences = 1;
+        assert!(!rules.is_valid());
+    }
+
+    #[test]
+    fn test_trigrams() {
+        // Same passage without any "ion"
+        let t = "The weather in the morning was rather interesting and \
+            pleasant for an early spring day in the northern hemisphere. \
+            Have you ever wondered whether the inner workings of nature \
+            can truly be understood through simple thinking about \
+            the patterns that emerge in everything around us? \
+            The ancient trees in the garden were standing tall and their \
+            branches reached toward the bright sky above. \
+            The morning air felt crisp and fresh. \
+            Another interesting thing happened when the river began to \
+            change its course and the water flowed in an entirely different \
+            manner than before. \
+            Is there anything more beautiful than a quiet evening spent \
+            reading by the fireplace?";
+        assert!(!verify_text(t.as_bytes(), &[]), "Missing 'ion' trigram should fail");
+        let mut rules = TextRules::DEFAULT;
+        rules.min_trigrams = 0;
+        assert!(verify_text_with_rules(t.as_bytes(), &[], &rules), "Passes with trigram check disabled");
+        assert!(verify_text(&natural_text(), &[]), "Natural text has all trigrams");
+    }
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(TextRules::preset(PRESET_STANDARD), Some(TextRules::DEFAULT));
+        assert_eq!(TextRules::preset(PRESET_STRICT), Some(TextRules::STRICT));
+        assert_eq!(TextRules::preset(PRESET_LENIENT), Some(TextRules::LENIENT));
+        assert_eq!(TextRules::preset(PRESET_TESTING), Some(TextRules::TESTING));
+        assert_eq!(TextRules::preset(4), None);
+
+        assert_eq!(TextRules::STRICT.max_sentences, 30);
+        assert_eq!(TextRules::STRICT.min_trigrams, 2);
+        assert_eq!(TextRules::LENIENT.min_trigrams, 0);
+        for id in PRESET_STANDARD..=PRESET_TESTING {
+            assert!(TextRules::preset(id).unwrap().is_valid());
+        }
+
+        // natural_text passes every preset
+        for id in PRESET_STANDARD..=PRESET_TESTING {
+            assert!(verify_text_with_rules(&natural_text(), &[], &TextRules::preset(id).unwrap()));
+        }
+
+        // A text with only one "ion" is borderline: standard passes, strict rejects
+        let t = String::from_utf8(natural_text()).unwrap().replace("change direction", "change its course");
+        assert!(verify_text_with_rules(t.as_bytes(), &[], &TextRules::DEFAULT));
+        assert!(!verify_text_with_rules(t.as_bytes(), &[], &TextRules::STRICT));
+        // Dropping the last "ion" flips standard too, but lenient still accepts it
+        let t = t.replace("observation and careful ", "");
+        assert!(!verify_text_with_rules(t.as_bytes(), &[], &TextRules::DEFAULT));
+        assert!(verify_text_with_rules(t.as_bytes(), &[], &TextRules::LENIENT));
+    }
+
+    #[test]
+    fn test_repeated_phrase() {
+        // "under the tall green" appears four times across distinct sentences
+        let t = "We walked under the tall green trees after lunch. \
+            Have you ever rested under the tall green trees on a warm afternoon in the middle of spring? \
+            Children were playing under the tall green trees while their parents talked about the \
+            weather and the harvest and everything that happened in town. \
+            Birds sang under the tall green trees. \
+            Another interesting thing happened when the river began to change direction in an unusual manner.";
+        assert!(!verify_text(t.as_bytes(), &[]), "Clause repeated four times should fail");
+        let mut rules = TextRules::DEFAULT;
+        rules.phrase_window = 0;
+        assert!(verify_text_with_rules(t.as_bytes(), &[], &rules), "Passes with phrase check disabled");
+        rules.phrase_window = 4;
+        rules.max_phrase_repeats = 4;
+        assert!(verify_text_with_rules(t.as_bytes(), &[], &rules), "Four repeats allowed at limit 4");
+    }
+
+    #[test]
+    fn test_mirrored() {
+        let half = &natural_text()[..300];
+        let mut t = half.to_vec();
+        t.extend(half.iter().rev());
+        assert!(is_mirrored(&t));
+        assert!(!verify_text(&t, &[]), "Mirrored text should fail");
+        assert!(is_mirrored(b"abcba"));
+        assert!(!is_mirrored(b"abcab"));
+
+        let text = natural_text();
+        assert!(!is_mirrored(&text));
+        assert!(verify_text(&text, &[]), "Natural text passes with the mirror check on");
+    }
+
+    #[test]
+    fn test_bigram_stuffed() {
+        // th/he/in/er/an sprinkled into otherwise meaningless tokens
+        let mut t = String::new();
+        let chunks = [
+            "Tha hin ero ane bod dalk? ",
+            "Kath meru lina sabe hola mant kito bura rane seli tomu vane hena. ",
+            "Pher dane linu hatu kone seri mabu. ",
+            "Rathu neli pane sorhe tinu valo mero kathe lune basi dora fenu \
+             polu rime sadu kovi nera hupa lesi mora tavu. ",
+            "Zinu bathe oker palu nesi. ",
+            "Lanu meho ruta sine kopa deru. ",
+            "Vethu nari sole mina pashe. ",
+        ];
+        for c in chunks.iter() { t.push_str(c); }
+        assert!(t.len() >= 256, "len={}", t.len());
+        assert!(!verify_text(t.as_bytes(), &[]), "Bigram-stuffed gibberish should fail");
+        let mut rules = TextRules::DEFAULT;
+        rules.min_trigrams = 0;
+        assert!(verify_text_with_rules(t.as_bytes(), &[], &rules), "Only the trigram check catches it");
+    }
+
+    #[test]
+    fn test_gibberish() {
+        let mut g = Vec::with_capacity(300);
+        let cons = b"bcdfghjklmnpqrstvwxyz";
+        for i in 0u16..300 {
+            if i % 7 == 0 { g.push(b' '); }
+            else if i % 50 == 49 { g.push(b'.'); }
+            else { g.push(cons[(i as usize) % cons.len()]); }
+        }
+        assert!(!verify_text(&g, &[]), "Gibberish should fail");
+    }
+}
@@ -0,0 +1,461 @@
+//! Word list and derivation for Proof of Inference.
+//!
+//! 200 common English words (4-8 letters), used to derive
+//! required words from the challenge seed deterministically.
+//!
+//! The compiled `WORDLIST` is the seeded default; the live list is the
+//! on-chain `Wordlist` account, read through the `WordSource` trait.
+
+pub const WORDLIST_SIZE: usize = 200;
+pub const MAX_REQUIRED: usize = 8;
+pub const MIN_WORD_LEN: usize = 4;
+pub const MAX_WORD_LEN: usize = 8;
+
+/// Upper bound on words in any (custom or default) list.
+pub const MAX_WORDLIST_SIZE: usize = 256;
+/// Packed buffer capacity: each word is stored as `[len, bytes...]`.
+pub const MAX_PACKED_LEN: usize = MAX_WORDLIST_SIZE * (1 + MAX_WORD_LEN);
+
+/// Difficulty breakpoints for the required word count: 3 words up to the first,
+/// one more past each, 8 past the last. Must be strictly increasing.
+pub const WORD_COUNT_THRESHOLDS: usize = 5;
+pub const DEFAULT_WORD_COUNT_THRESHOLDS: [u64; WORD_COUNT_THRESHOLDS] = [10, 15, 20, 30, 40];
+
+/// u64 words in a bitmap over wordlist indices (one bit per possible word).
+pub const WORD_BITMAP_LEN: usize = MAX_WORDLIST_SIZE / 64;
+
+/// Word categories: nouns, verbs, adjectives, adverbs.
+pub const CATEGORY_COUNT: usize = 4;
+/// Exclusive end index of each category in `WORDLIST`.
+pub const DEFAULT_CATEGORY_ENDS: [u16; CATEGORY_COUNT] = [70, 120, 170, 200];
+
+pub const WORDLIST: [&str; WORDLIST_SIZE] = [
+    // Nouns (70)
+    "time","life","world","place","water","light","house","music","power","dream",
+    "heart","earth","ocean","river","cloud","stone","flame","voice","night","field",
+    "space","brain","truth","peace","storm","tower","plant","metal","glass","wheel",
+    "bridge","forest","garden","market","island","desert","silver","shadow","spirit","nature",
+    "energy","future","memory","moment","season","winter","summer","signal","system","design",
+    "method","reason","answer","letter","person","animal","flower","morning","evening","journey",
+    "history","culture","balance","freedom","pattern","shelter","surface","chapter","element","silence",
+    // Verbs (50)
+    "think","learn","build","write","speak","dance","climb","watch","shine","carry",
+    "drive","paint","teach","reach","solve","share","trust","guide","shape","craft",
+    "chase","drift","weave","bloom","grasp","shift","sweep","trace","wander","gather",
+    "create","follow","listen","notice","wonder","happen","become","remain","travel","return",
+    "search","reveal","explore","imagine","connect","protect","reflect","develop","consider","discover",
+    // Adjectives (50)
+    "bright","quiet","gentle","strong","simple","hidden","golden","silent","frozen","bitter",
+    "tender","vivid","subtle","fierce","humble","steady","clever","honest","broken","sacred",
+    "unique","global","active","native","smooth","narrow","liquid","mental","social","visual",
+    "formal","casual","proper","remote","secure","stable","cosmic","ancient","modern","natural",
+    "digital","central","special","private","perfect","strange","careful","curious","distant","endless",
+    // Adverbs (30)
+    "often","never","always","slowly","deeply","gently","simply","nearly","barely","mostly",
+    "partly","surely","truly","fully","quite","still","maybe","hence","twice","ahead",
+    "apart","aside","along","after","again","early","later","since","almost","around",
+];
+
+// Every compiled word fits a derivation slot: `derive_words` truncates at
+// `MAX_WORD_LEN`, so an overlong entry would never match `verify_text`.
+const _: () = {
+    let mut i = 0;
+    while i < WORDLIST_SIZE {
+        let n = WORDLIST[i].len();
+        assert!(n >= MIN_WORD_LEN && n <= MAX_WORD_LEN, "WORDLIST entry outside 4-8 bytes");
+        i += 1;
+    }
+};
+
+/// Read access to a wordlist, by index.
+pub trait WordSource {
+    fn word_count(&self) -> usize;
+    fn word(&self, idx: usize) -> &[u8];
+
+    /// Exclusive end index of each category; all zeros means uncategorized.
+    fn category_ends(&self) -> [u16; CATEGORY_COUNT] {
+        [0; CATEGORY_COUNT]
+    }
+}
+
+/// The compiled `WORDLIST` with its category ranges.
+pub struct DefaultWords;
+
+impl WordSource for DefaultWords {
+    fn word_count(&self) -> usize {
+        WORDLIST_SIZE
+    }
+
+    fn word(&self, idx: usize) -> &[u8] {
+        WORDLIST[idx].as_bytes()
+    }
+
+    fn category_ends(&self) -> [u16; CATEGORY_COUNT] {
+        DEFAULT_CATEGORY_ENDS
+    }
+}
+
+impl WordSource for [&str] {
+    fn word_count(&self) -> usize {
+        self.len()
+    }
+
+    fn word(&self, idx: usize) -> &[u8] {
+        self[idx].as_bytes()
+    }
+}
+
+/// Length-prefixed packed wordlist: `[len, bytes...][len, bytes...]...`
+pub struct PackedWords<'a> {
+    data: &'a [u8],
+    count: usize,
+    category_ends: [u16; CATEGORY_COUNT],
+}
+
+impl<'a> PackedWords<'a> {
+    pub fn new(data: &'a [u8], count: usize, category_ends: [u16; CATEGORY_COUNT]) -> Self {
+        PackedWords { data, count, category_ends }
+    }
+}
+
+impl WordSource for PackedWords<'_> {
+    fn word_count(&self) -> usize {
+        self.count
+    }
+
+    fn category_ends(&self) -> [u16; CATEGORY_COUNT] {
+        self.category_ends
+    }
+
+    fn word(&self, idx: usize) -> &[u8] {
+        let mut pos = 0;
+        let mut i = 0;
+        while pos < self.data.len() {
+            let len = self.data[pos] as usize;
+            let end = (pos + 1 + len).min(self.data.len());
+            if i == idx {
+                return &self.data[pos + 1..end];
+            }
+            pos = end;
+            i += 1;
+        }
+        &[]
+    }
+}
+
+/// Category ends must be non-decreasing and within the list.
+pub fn is_valid_category_ends(ends: &[u16; CATEGORY_COUNT], count: usize) -> bool {
+    let mut prev = 0;
+    for &end in ends.iter() {
+        if end < prev || end as usize > count {
+            return false;
+        }
+        prev = end;
+    }
+    true
+}
+
+/// A valid wordlist entry is 4–8 lowercase ASCII letters.
+pub fn is_valid_word(word: &[u8]) -> bool {
+    word.len() >= MIN_WORD_LEN
+        && word.len() <= MAX_WORD_LEN
+        && word.iter().all(|b| b.is_ascii_lowercase())
+}
+
+/// Derived required words (fixed-size, no heap).
+pub struct RequiredWords {
+    pub words: [[u8; MAX_WORD_LEN]; MAX_REQUIRED],
+    pub lens: [usize; MAX_REQUIRED],
+    pub indices: [u16; MAX_REQUIRED],
+    pub count: usize,
+}
+
+impl RequiredWords {
+    /// The `i`th required word.
+    pub fn word(&self, i: usize) -> &[u8] {
+        &self.words[i][..self.lens[i]]
+    }
+
+    /// Set the bits of these words' wordlist indices in `bitmap`.
+    pub fn mark(&self, bitmap: &mut [u64; WORD_BITMAP_LEN]) {
+        let mut i = 0;
+        while i < self.count {
+            let idx = self.indices[i] as usize;
+            bitmap[idx / 64] |= 1 << (idx % 64);
+            i += 1;
+        }
+    }
+}
+
+/// Map difficulty to required word count: 3, plus one per threshold it exceeds.
+pub fn word_count_for_difficulty(difficulty: u64, thresholds: &[u64; WORD_COUNT_THRESHOLDS]) -> usize {
+    let mut count = MAX_REQUIRED - WORD_COUNT_THRESHOLDS;
+    let mut i = 0;
+    while i < WORD_COUNT_THRESHOLDS {
+        if difficulty > thresholds[i] { count += 1; }
+        i += 1;
+    }
+    count
+}
+
+/// Thresholds must be strictly increasing.
+pub fn is_valid_word_count_thresholds(thresholds: &[u64; WORD_COUNT_THRESHOLDS]) -> bool {
+    thresholds.windows(2).all(|w| w[0] < w[1])
+}
+
+/// Linear probe from `start` for an unused index in `lo..hi`.
+fn probe_unused(used: &[bool], start: usize, lo: usize, hi: usize) -> Option<usize> {
+    let span = hi - lo;
+    let mut idx = start;
+    let mut tries = 0;
+    while used[idx] && tries < span {
+        idx = lo + (idx - lo + 1) % span;
+        tries += 1;
+    }
+    if tries >= span { None } else { Some(idx) }
+}
+
+/// Derive required words deterministically from challenge seed and difficulty.
+///
+/// When 4+ words are required and the source is categorized, the first four
+/// slots draw one word from each category (starting category rotated by the
+/// seed) so every challenge mixes nouns, verbs, adjectives and adverbs.
+pub fn derive_words<S: WordSource + ?Sized>(seed: &[u8; 32], difficulty: u64, source: &S) -> RequiredWords {
+    derive_words_with_thresholds(seed, difficulty, &DEFAULT_WORD_COUNT_THRESHOLDS, source)
+}
+
+/// `derive_words` with explicit word-count `thresholds` (on-chain: `MineState.word_count_thresholds`).
+pub fn derive_words_with_thresholds<S: WordSource + ?Sized>(
+    seed: &[u8; 32],
+    difficulty: u64,
+    thresholds: &[u64; WORD_COUNT_THRESHOLDS],
+    source: &S,
+) -> RequiredWords {
+    derive_words_excluding(seed, difficulty, thresholds, &[0; WORD_BITMAP_LEN], source)
+}
+
+/// `derive_words_with_thresholds` skipping the indices set in `recent`
+/// (on-chain: `MineState.recent_words`). If that leaves too few words for the
+/// count, the words are drawn as if `recent` were empty.
+pub fn derive_words_avoiding<S: WordSource + ?Sized>(
+    seed: &[u8; 32],
+    difficulty: u64,
+    thresholds: &[u64; WORD_COUNT_THRESHOLDS],
+    recent: &[u64; WORD_BITMAP_LEN],
+    source: &S,
+) -> RequiredWords {
+    let result = derive_words_excluding(seed, difficulty, thresholds, recent, source);
+    if result.count < word_count_for_difficulty(difficulty, thresholds) {
+        return derive_words_with_thresholds(seed, difficulty, thresholds, source);
+    }
+    result
+}
+
+fn derive_words_excluding<S: WordSource + ?Sized>(
+    seed: &[u8; 32],
+    difficulty: u64,
+    thresholds: &[u64; WORD_COUNT_THRESHOLDS],
+    excluded: &[u64; WORD_BITMAP_LEN],
+    source: &S,
+) -> RequiredWords {
+    let count = word_count_for_difficulty(difficulty, thresholds);
+    let list_size = source.word_count().min(MAX_WORDLIST_SIZE);
+
+    let mut result = RequiredWords {
+        words: [[0u8; MAX_WORD_LEN]; MAX_REQUIRED],
+        lens: [0; MAX_REQUIRED],
+        indices: [0; MAX_REQUIRED],
+        count,
+    };
+
+    if list_size == 0 {
+        result.count = 0;
+        return result;
+    }
+
+    let ends = source.category_ends();
+    let categorized = count >= CATEGORY_COUNT && is_valid_category_ends(&ends, list_size) && ends[0] > 0;
+    let rotation = seed[MAX_REQUIRED * 2] as usize % CATEGORY_COUNT;
+
+    let mut used = [false; MAX_WORDLIST_SIZE];
+    let mut k = 0;
+    while k < MAX_WORDLIST_SIZE {
+        used[k] = excluded[k / 64] & (1 << (k % 64)) != 0;
+        k += 1;
+    }
+
+    let mut i = 0;
+    while i < count {
+        let raw = ((seed[i * 2] as u16) << 8) | (seed[i * 2 + 1] as u16);
+
+        // Category slot: draw within that category's index range first
+        let mut picked = None;
+        if categorized && i < CATEGORY_COUNT {
+            let cat = (i + rotation) % CATEGORY_COUNT;
+            let lo = if cat == 0 { 0 } else { ends[cat - 1] as usize };
+            let hi = ends[cat] as usize;
+            if hi > lo {
+                picked = probe_unused(&used, lo + (raw as usize) % (hi - lo), lo, hi);
+            }
+        }
+
+        // Uniform pick, skipping duplicates
+        if picked.is_none() {
+            picked = probe_unused(&used, (raw as usize) % list_size, 0, list_size);
+        }
+        let idx = match picked {
+            Some(idx) => idx,
+            None => {
+                result.count = i;
+                break;
+            }
+        };
+
+        used[idx] = true;
+        result.indices[i] = idx as u16;
+        let word = source.word(idx);
+        // Lists are validated on upload (`is_valid_word`); truncation only guards the copy.
+        debug_assert!(word.len() <= MAX_WORD_LEN);
+        let len = word.len().min(MAX_WORD_LEN);
+        let mut j = 0;
+        while j < len {
+            result.words[i][j] = word[j];
+            j += 1;
+        }
+        result.lens[i] = len;
+
+        i += 1;
+    }
+
+    result
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(list: &[&str]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for w in list {
+            data.push(w.len() as u8);
+            data.extend_from_slice(w.as_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_packed_matches_compiled() {
+        let data = pack(&WORDLIST);
+        let packed = PackedWords::new(&data, WORDLIST_SIZE, DEFAULT_CATEGORY_ENDS);
+        let seed = [0x5au8; 32];
+        let a = derive_words(&seed, 45, &DefaultWords);
+        let b = derive_words(&seed, 45, &packed);
+        assert_eq!(a.count, b.count);
+        assert_eq!(a.words, b.words);
+        assert_eq!(a.lens, b.lens);
+    }
+
+    #[test]
+    fn test_custom_list() {
+        let data = pack(&["alpha", "bravo", "delta", "kilo"]);
+        let packed = PackedWords::new(&data, 4, [0; CATEGORY_COUNT]);
+        let rw = derive_words(&[3u8; 32], 8, &packed);
+        assert_eq!(rw.count, 3);
+        for i in 0..rw.count {
+            assert!(is_valid_word(&rw.words[i][..rw.lens[i]]));
+        }
+    }
+
+    fn category_of(word: &[u8]) -> usize {
+        let idx = WORDLIST.iter().position(|w| w.as_bytes() == word).unwrap();
+        DEFAULT_CATEGORY_ENDS.iter().position(|&end| idx < end as usize).unwrap()
+    }
+
+    #[test]
+    fn test_category_coverage() {
+        // difficulty 11 → 4 words, 16 → 5, 45 → 8
+        for difficulty in [11u64, 16, 45] {
+            for s in 0u8..=255 {
+                let mut seed = [0u8; 32];
+                for (k, b) in seed.iter_mut().enumerate() {
+                    *b = s.wrapping_mul(31).wrapping_add((k as u8).wrapping_mul(17));
+                }
+                let rw = derive_words(&seed, difficulty, &DefaultWords);
+                let mut seen = [false; CATEGORY_COUNT];
+                for i in 0..rw.count {
+                    seen[category_of(&rw.words[i][..rw.lens[i]])] = true;
+                }
+                assert!(seen.iter().all(|&c| c), "difficulty {} seed {} missing a category", difficulty, s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_word_count_thresholds() {
+        let d = &DEFAULT_WORD_COUNT_THRESHOLDS;
+        let counts: Vec<usize> = [4u64, 10, 11, 15, 16, 20, 21, 30, 31, 40, 41, 250]
+            .iter()
+            .map(|&x| word_count_for_difficulty(x, d))
+            .collect();
+        assert_eq!(counts, [3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8]);
+
+        // Raise the PoW difficulty without making the text harder: 3 words up to 60
+        let custom = [60, 80, 100, 120, 140];
+        assert!(is_valid_word_count_thresholds(&custom));
+        assert_eq!(word_count_for_difficulty(45, &custom), 3);
+        assert_eq!(word_count_for_difficulty(61, &custom), 4);
+        assert_eq!(word_count_for_difficulty(141, &custom), 8);
+        let seed = [5u8; 32];
+        assert_eq!(derive_words_with_thresholds(&seed, 45, &custom, &DefaultWords).count, 3);
+        assert_eq!(derive_words(&seed, 45, &DefaultWords).count, 8);
+
+        assert!(is_valid_word_count_thresholds(d));
+        assert!(!is_valid_word_count_thresholds(&[10, 15, 15, 30, 40]));
+        assert!(!is_valid_word_count_thresholds(&[10, 15, 20, 40, 30]));
+        assert!(!is_valid_word_count_thresholds(&[0; WORD_COUNT_THRESHOLDS]));
+    }
+
+    #[test]
+    fn test_derive_words_avoiding() {
+        let d = &DEFAULT_WORD_COUNT_THRESHOLDS;
+        let mut recent = [0u64; WORD_BITMAP_LEN];
+        let first = derive_words_with_thresholds(&[7u8; 32], 45, d, &DefaultWords);
+        first.mark(&mut recent);
+        assert_eq!(recent.iter().map(|w| w.count_ones()).sum::<u32>(), 8);
+
+        // The same seed again draws 8 fresh words
+        let again = derive_words_avoiding(&[7u8; 32], 45, d, &recent, &DefaultWords);
+        assert_eq!(again.count, 8);
+        for i in 0..again.count {
+            let idx = again.indices[i] as usize;
+            assert_eq!(recent[idx / 64] & (1 << (idx % 64)), 0);
+            assert_eq!(&again.words[i][..again.lens[i]], WORDLIST[idx].as_bytes());
+        }
+
+        // Nothing left to draw from: fall back to the plain derivation
+        let full = [u64::MAX; WORD_BITMAP_LEN];
+        let fallback = derive_words_avoiding(&[7u8; 32], 45, d, &full, &DefaultWords);
+        assert_eq!(fallback.count, 8);
+        assert_eq!(fallback.indices, first.indices);
+    }
+
+    #[test]
+    fn test_category_ends_validation() {
+        assert!(is_valid_category_ends(&DEFAULT_CATEGORY_ENDS, WORDLIST_SIZE));
+        assert!(is_valid_category_ends(&[0; CATEGORY_COUNT], 10));
+        assert!(!is_valid_category_ends(&[70, 60, 170, 200], WORDLIST_SIZE));
+        assert!(!is_valid_category_ends(&[70, 120, 170, 201], WORDLIST_SIZE));
+    }
+
+    #[test]
+    fn test_valid_word() {
+        assert!(is_valid_word(b"time"));
+        assert!(is_valid_word(b"consider"));
+        assert!(!is_valid_word(b"sky"));
+        assert!(!is_valid_word(b"discovery"));
+        assert!(!is_valid_word(b"Time"));
+        assert!(!is_valid_word(b"it's"));
+    }
+}
@@ -0,0 +1,5898 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{keccak, sysvar};
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount};
+
+use crate::verify::{self, TextRules, TextRulesExt};
+use crate::words;
+
+declare_id!("AcTXBfHAJgwt1sTn3DvTSKiiCKgShzGEZzq2zQrs5BnG");
+
+// ============================================================
+// Constants
+// ============================================================
+
+const MAX_SUPPLY_TOKENS: u64 = 100_000_000_000;            // 100B CRB, scaled by 10^decimals
+const INITIAL_REWARD_TOKENS: u64 = 25_000;                  // 25K CRB, scaled by 10^decimals
+const MAX_DECIMALS: u8 = 9;
+const HALVING_INTERVAL: u64 = 2_000_000;
+const EPOCH_DURATION: i64 = 600;                            // 10 min
+const MAX_EPOCH_SPAN: i64 = 24 * 3600;                      // emergency extensions stop at 24h per epoch
+const MIN_ADVANCE_INTERVAL: i64 = 30;                       // wall-clock floor between advances
+const TARGET_SOLUTIONS: u64 = 50;
+const MAX_TARGET_SOLUTIONS: u64 = 1_000;                    // ceiling for the network-scaled target
+const DEFAULT_MAX_REPORTED_SOLUTIONS: u64 = 100 * MAX_TARGET_SOLUTIONS; // crank-reported count ceiling
+const MAX_SUBMITS_PER_SECOND: u64 = 5_000;                  // above any rate the cluster can land submits at
+const MAX_RECENT_WORD_EPOCHS: u8 = 8;                       // 8 epochs × 8 words stays well under a 200-word list
+const INITIAL_DIFFICULTY: u64 = 8;
+const MAX_DIFFICULTY: u64 = 250;                            // defaults for MineState's difficulty bounds
+const MIN_DIFFICULTY: u64 = 4;
+const MAX_DIFFICULTY_ADJ: u64 = 5;
+const HASH_BITS: u64 = 256;                                 // no hash has more leading zero bits
+const DIFFICULTY_EMA_ONE: u64 = 1 << 16;                    // fixed-point 1.0 for difficulty_ema
+const DIFFICULTY_EMA_WINDOW: u64 = 8;                       // EMA smoothing period (epochs)
+const MAX_MIN_HASH_WEIGHT: u8 = 3;                          // cap on min-hash bonus bits per epoch
+const CLAIM_EXPIRY_EPOCHS: u64 = 500;                       // default claim window
+const MIN_CLAIM_EXPIRY_EPOCHS: u64 = 10;
+const VESTING_DURATION: i64 = 30 * 24 * 3600;              // 30 days in seconds
+const WORDLIST_MAX_BYTES: usize = words::MAX_PACKED_LEN;
+const MAX_CRANKS: usize = 5;
+const EPOCH_HISTORY_LEN: usize = 32;                        // EpochHistory ring size
+const MAX_HISTORY_PAGE: u64 = 16;                           // records per dump_epoch_history call
+const TREND_WINDOW: usize = 8;                              // recent epochs difficulty_trend averages
+const MAX_SOLUTIONS_PER_MINER: u8 = 16;                     // cap on the per-epoch solution index range
+const MAX_COUNTER_SHARDS: u8 = 16;                          // cap on SolutionCounter shards per epoch
+const MAX_CLAIM_TIP_BPS: u16 = 1_000;                       // keeper tip cap: 10% of a claim
+const MAX_ADVANCE_BONUS_DIVISOR: u64 = 10;                   // advance bonus ≤ initial_reward / 10
+const MAX_BONUS_BITS: u64 = 8;                              // +1/8 reward per bit over difficulty, up to 2×
+const BPS_ONE: u16 = 10_000;
+const NO_BEST_HASH: [u8; 32] = [u8::MAX; 32];               // best_hash before any claim: every hash beats it
+const MAX_BOOST_BPS: u16 = 30_000;                          // early-adopter boost cap: 3×
+const MAX_WORD_REWARD_BPS: u16 = 5_000;                     // per extra required word: +50%
+const MAX_SUBMIT_CUTOFF_BPS: u16 = 5_000;                   // submits may close for at most the last half of an epoch
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;                  // base fee, one signer per mining tx
+const SUMMARY_VERSION: u8 = 2;                              // bump when MineSummary fields change
+const SCHEMA_VERSION: u8 = 22;                               // MineState layout; see MineState docs
+
+// `feature_flags` bits: optional features currently enabled in MineState.
+pub const FEATURE_HALTED: u32 = 1 << 0;
+pub const FEATURE_CHAIN_MODE: u32 = 1 << 1;
+pub const FEATURE_DEDUP_TEXTS: u32 = 1 << 2;
+pub const FEATURE_COUNTER_SHARDS: u32 = 1 << 3;
+pub const FEATURE_CHALLENGE_PREVIEW: u32 = 1 << 4;
+pub const FEATURE_NORMALIZE_OVERRUN: u32 = 1 << 5;
+pub const FEATURE_STOP_AFTER_EMISSION: u32 = 1 << 6;
+pub const FEATURE_EARLY_BOOST: u32 = 1 << 7;
+pub const FEATURE_CLAIM_TIP: u32 = 1 << 8;
+pub const FEATURE_SUBMIT_DEPOSIT: u32 = 1 << 9;
+pub const FEATURE_MIN_HASH: u32 = 1 << 10;
+pub const FEATURE_MULTI_SOLUTION: u32 = 1 << 11;
+pub const FEATURE_CLAIM_REQUIRES_ADVANCE: u32 = 1 << 12;
+pub const FEATURE_PERMISSIONLESS_ADVANCE: u32 = 1 << 13;
+pub const FEATURE_TARGET_SCALING: u32 = 1 << 14;
+pub const FEATURE_RECENT_WORDS: u32 = 1 << 15;
+pub const FEATURE_EMPTY_EPOCH_GRACE: u32 = 1 << 16;
+pub const FEATURE_BOUNDARY_MODE: u32 = 1 << 17;
+pub const FEATURE_WORD_REWARD: u32 = 1 << 18;
+pub const FEATURE_SUBMIT_CUTOFF: u32 = 1 << 19;
+pub const FEATURE_REPEAT_HASH_CHECK: u32 = 1 << 20;
+pub const FEATURE_REPORT_CEILING: u32 = 1 << 21;
+
+// `health_check` bits: MineState invariants that failed.
+pub const HEALTH_DIFFICULTY_RANGE: u32 = 1 << 0;    // difficulty outside min_difficulty..=max_difficulty
+pub const HEALTH_DIFFICULTY_BOUNDS: u32 = 1 << 1;   // the bounds themselves invalid
+pub const HEALTH_EPOCH_TIMES: u32 = 1 << 2;         // epoch_end_time not after epoch_start_time
+pub const HEALTH_SUPPLY: u32 = 1 << 3;              // total_supply past max_supply
+pub const HEALTH_MINT: u32 = 1 << 4;                // mint is not the ["mint"] PDA
+pub const HEALTH_TEXT_RULES: u32 = 1 << 5;          // text_rules or ext_rules() out of bounds
+pub const HEALTH_SCHEMA: u32 = 1 << 6;              // version is not SCHEMA_VERSION (needs migrate_mine_state)
+pub const HEALTH_CRANK_SET: u32 = 1 << 7;           // crank_count past MAX_CRANKS
+pub const HEALTH_GOVERNANCE: u32 = 1 << 8;          // no governance key
+
+// ============================================================
+// Program
+// ============================================================
+
+#[program]
+pub mod proof_of_inference {
+    use super::*;
+
+    /// Initialize the mining state and create the SPL token mint.
+    ///
+    /// `decimals` sets the mint precision; the supply cap and reward are scaled by `10^decimals`.
+    /// `guardian` holds the emergency halt switch and should be a different key from the crank.
+    /// `governance` sets parameters; the payer becomes crank authority and only advances epochs.
+    ///
+    /// Both PDAs are fixed, so this runs once; a second call fails with `AlreadyInitialized`.
+    /// On a MineState still on an older, shorter layout the guard is never
+    /// reached: the call fails earlier with Anchor's `AccountDidNotDeserialize`.
+    pub fn initialize(ctx: Context<Initialize>, decimals: u8, guardian: Pubkey, governance: Pubkey) -> Result<()> {
+        let (initial_reward, max_supply) = scaled_supply(decimals).ok_or(ErrorCode::InvalidDecimals)?;
+        let clock = Clock::get()?;
+        let mine_state_key = ctx.accounts.mine_state.key();
+        let mint_key = ctx.accounts.mint.key();
+        let bump = ctx.bumps.mine_state;
+
+        let seed_input = [
+            clock.slot.to_le_bytes().as_ref(),
+            clock.unix_timestamp.to_le_bytes().as_ref(),
+            mine_state_key.as_ref(),
+        ]
+        .concat();
+        let challenge_seed = keccak::hash(&seed_input).to_bytes();
+
+        let state = &mut ctx.accounts.mine_state;
+        state.total_mined = 0;
+        state.difficulty = INITIAL_DIFFICULTY;
+        state.difficulty_ema = INITIAL_DIFFICULTY * DIFFICULTY_EMA_ONE;
+        state.challenge_seed = challenge_seed;
+        state.epoch_number = 0;
+        state.epoch_start_time = clock.unix_timestamp;
+        state.epoch_end_time = clock.unix_timestamp + EPOCH_DURATION;
+        state.solutions_in_epoch = 0;
+        state.settled_in_epoch = 0;
+        state.total_supply = 0;
+        state.mint = mint_key;
+        state.crank_authority = ctx.accounts.payer.key();
+        state.pending_crank_authority = None;
+        state.crank_authorities = [Pubkey::default(); MAX_CRANKS];
+        state.crank_count = 0;
+        state.guardian = guardian;
+        state.governance = governance;
+        state.pending_governance = None;
+        state.version = SCHEMA_VERSION;
+        state.min_difficulty = MIN_DIFFICULTY;
+        state.max_difficulty = MAX_DIFFICULTY;
+        state.max_difficulty_adj = MAX_DIFFICULTY_ADJ;
+        state.claim_requires_advance = false;
+        state.word_count_thresholds = words::DEFAULT_WORD_COUNT_THRESHOLDS;
+        state.permissionless_advance = false;
+        state.advance_bonus = 0;
+        state.unique_miners = 0;
+        state.target_per_miner_bps = 0;
+        state.max_target_solutions = TARGET_SOLUTIONS;
+        state.recent_word_epochs = 0;
+        state.recent_words_age = 0;
+        state.recent_words = [0; words::WORD_BITMAP_LEN];
+        state.empty_epoch_grace = 0;
+        state.consecutive_empty_epochs = 0;
+        state.set_ext_rules(&TextRulesExt::DEFAULT);
+        state.word_reward_bps = 0;
+        state.submit_cutoff_bps = 0;
+        state.reject_repeat_hash = false;
+        state.max_reported_solutions = DEFAULT_MAX_REPORTED_SOLUTIONS;
+        state.best_hash = NO_BEST_HASH;
+        state.best_hash_miner = Pubkey::default();
+        state.halted = false;
+        state.last_advance_time = clock.unix_timestamp;
+        state.min_hash_weight = 0;
+        state.claim_expiry_epochs = CLAIM_EXPIRY_EPOCHS;
+        state.submit_deposit = 0;
+        state.dedup_texts = false;
+        state.max_solutions_per_miner = 1;
+        state.preview_lead_seconds = 0;
+        state.next_challenge_seed = None;
+        state.counter_shards = 0;
+        state.next_counter_shards = 0;
+        state.emission_ended = false;
+        state.stop_after_emission = false;
+        state.claim_tip_bps = 0;
+        state.normalize_overrun = false;
+        state.boost_epochs = 0;
+        state.boost_multiplier_bps = BPS_ONE;
+        state.bump = bump;
+        state.chain_mode = false;
+        state.text_rules = TextRules::DEFAULT;
+        state.advance_bounty = 0;
+        state.decimals = decimals;
+        state.initial_reward = initial_reward;
+        state.max_supply = max_supply;
+
+        Ok(())
+    }
+
+    /// Submit a mining solution.
+    ///
+    /// mine_state is READ-ONLY — zero write-lock contention.
+    /// Each submit only creates a unique Solution PDA.
+    ///
+    /// `slot` picks the SlotHashes entry the hash is bound to; it must still be in the sysvar.
+    /// `index` (below `max_solutions_per_miner`) lets one miner hold several solutions per epoch.
+    /// `recipient` is whose VestingAccount the claim credits; `Pubkey::default()` means the miner.
+    pub fn submit_solution(
+        ctx: Context<SubmitSolution>,
+        text: String,
+        nonce: u64,
+        recipient: Pubkey,
+        slot: u64,
+        index: u64,
+    ) -> Result<()> {
+        process_submit(ctx.accounts, &ctx.bumps, text.as_bytes(), nonce, recipient, slot, index)
+    }
+
+    /// Commit to a solution before revealing it: stores `keccak(miner | text | nonce)`.
+    ///
+    /// Optional; `reveal_solution` must follow in the same or the next epoch.
+    pub fn commit_solution(ctx: Context<CommitSolution>, commitment: [u8; 32]) -> Result<()> {
+        let epoch = ctx.accounts.mine_state.epoch_number;
+        let c = &mut ctx.accounts.commitment;
+        c.miner = ctx.accounts.miner.key();
+        c.commitment = commitment;
+        c.epoch = epoch;
+        c.bump = ctx.bumps.commitment;
+        Ok(())
+    }
+
+    /// Reveal a committed solution and run the normal submit checks.
+    /// The Commitment PDA is closed back to the miner.
+    pub fn reveal_solution(
+        ctx: Context<RevealSolution>,
+        text: String,
+        nonce: u64,
+        recipient: Pubkey,
+        slot: u64,
+        index: u64,
+    ) -> Result<()> {
+        let miner_key = ctx.accounts.submit.miner.key();
+        let commitment = &ctx.accounts.commitment;
+        require!(
+            solution_commitment(&miner_key, text.as_bytes(), nonce) == commitment.commitment,
+            ErrorCode::CommitmentMismatch
+        );
+        require!(
+            reveal_in_window(commitment.epoch, ctx.accounts.submit.mine_state.epoch_number),
+            ErrorCode::RevealWindowClosed
+        );
+
+        process_submit(&mut ctx.accounts.submit, &ctx.bumps.submit, text.as_bytes(), nonce, recipient, slot, index)?;
+        ctx.accounts.commitment.close(ctx.accounts.submit.miner.to_account_info())
+    }
+
+    /// Close an unrevealed commitment and recover its rent.
+    pub fn cancel_commitment(_ctx: Context<CancelCommitment>) -> Result<()> {
+        // Commitment PDA closed by Anchor `close` constraint → rent to miner
+        Ok(())
+    }
+
+    /// Create a MinerConfig for a miner. Required before submitting in chain mode.
+    pub fn create_miner_config(ctx: Context<CreateMinerConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.miner_config;
+        config.miner = ctx.accounts.miner.key();
+        config.prior_hash = [0u8; 32];
+        config.bump = ctx.bumps.miner_config;
+        Ok(())
+    }
+
+    /// Create a VestingAccount for a miner. Called once before first claim.
+    pub fn create_vesting(ctx: Context<CreateVesting>) -> Result<()> {
+        let v = &mut ctx.accounts.vesting;
+        v.miner = ctx.accounts.miner.key();
+        v.locked = 0;
+        v.unlocked = 0;
+        v.last_update = Clock::get()?.unix_timestamp;
+        v.bump = ctx.bumps.vesting;
+        Ok(())
+    }
+
+    /// Claim reward for a submitted solution.
+    ///
+    /// Does NOT mint tokens directly. Instead, adds reward to VestingAccount.locked
+    /// of the solution's payee (its `recipient`, or the miner when that is zeroed).
+    /// Tokens are minted later via `withdraw` as they vest over VESTING_DURATION.
+    /// The Solution's rent and deposit, and any TextSeen rent, go to the payee too.
+    ///
+    /// Anyone may call this for the miner. A caller other than the miner who
+    /// passes a token account (plus `mint` and `token_program`) is minted
+    /// `claim_tip_bps` of the reward; the rest vests for the payee as usual.
+    /// In chain mode only the miner may claim, since the claim moves the miner's
+    /// chain tip and would void the nonces it is grinding against the old one.
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        let clock = Clock::get()?;
+
+        // ── Read state ──
+        let current_epoch = ctx.accounts.mine_state.epoch_number;
+        let epoch_end_time = ctx.accounts.mine_state.epoch_end_time;
+        let total_supply = ctx.accounts.mine_state.total_supply;
+        let max_supply = ctx.accounts.mine_state.max_supply;
+        let chain_mode = ctx.accounts.mine_state.chain_mode;
+        let solution_epoch = ctx.accounts.solution.epoch;
+        let solution_hash = ctx.accounts.solution.hash;
+        let claim_expiry_epochs = ctx.accounts.mine_state.claim_expiry_epochs;
+
+        require!(!ctx.accounts.mine_state.halted, ErrorCode::Halted);
+        require!(
+            claim_caller_allowed(chain_mode, &ctx.accounts.caller.key(), &ctx.accounts.solution.miner),
+            ErrorCode::DelegatedClaimInChainMode
+        );
+
+        // ── Defense in depth: the stored hash must still meet its submit-time difficulty ──
+        require!(ctx.accounts.solution.meets_difficulty(), ErrorCode::SolutionHashInvalid);
+
+        // ── Solution's epoch must have ended (and, if required, been advanced) ──
+        let requires_advance = ctx.accounts.mine_state.claim_requires_advance;
+        require!(
+            epoch_claimable(solution_epoch, current_epoch, clock.unix_timestamp, epoch_end_time, requires_advance),
+            ErrorCode::EpochNotEnded
+        );
+
+        // ── Not expired ──
+        require!(
+            !claim_expired(current_epoch, solution_epoch, claim_expiry_epochs),
+            ErrorCode::ClaimExpired
+        );
+
+        // ── Reward: fixed at submit, clipped to what the supply cap still allows ──
+        let actual_reward = ctx.accounts.solution.payout(total_supply, max_supply);
+
+        // ── Keeper tip: only for a third-party caller with a token account ──
+        let caller = ctx.accounts.caller.key();
+        let delegated = caller != ctx.accounts.solution.miner && caller != ctx.accounts.payee.key();
+        let tip = match (&ctx.accounts.caller_token_account, &ctx.accounts.mint, &ctx.accounts.token_program) {
+            (Some(to), Some(mint), Some(token_program)) if delegated => {
+                let tip = claim_tip(actual_reward, ctx.accounts.mine_state.claim_tip_bps);
+                if tip > 0 {
+                    let seeds = &[b"mine_state".as_ref(), &[ctx.accounts.mine_state.bump]];
+                    token::mint_to(
+                        CpiContext::new_with_signer(
+                            token_program.to_account_info(),
+                            MintTo {
+                                mint: mint.to_account_info(),
+                                to: to.to_account_info(),
+                                authority: ctx.accounts.mine_state.to_account_info(),
+                            },
+                            &[&seeds[..]],
+                        ),
+                        tip,
+                    )?;
+                }
+                tip
+            }
+            _ => 0,
+        };
+
+        // ── Update vesting ──
+        let vesting = &mut ctx.accounts.vesting;
+
+        // Accrue any pending vested amount
+        drip_vesting(vesting, clock.unix_timestamp);
+
+        // Add new reward to locked
+        vesting.locked = vesting.locked.checked_add(actual_reward - tip).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        // ── Extend the miner's solution chain ──
+        match ctx.accounts.miner_config.as_mut() {
+            Some(config) => config.prior_hash = solution_hash,
+            None => require!(!chain_mode, ErrorCode::MinerConfigRequired),
+        }
+
+        // ── Update mine state (reserve supply, no mint yet) ──
+        let state = &mut ctx.accounts.mine_state;
+        state.record_claim(actual_reward)?;
+        if state.record_best_hash(&solution_hash, &ctx.accounts.solution.miner) {
+            emit!(NewBestHash {
+                miner: state.best_hash_miner,
+                hash: state.best_hash,
+                leading_zero_bits: leading_zero_bits(&state.best_hash),
+                epoch: solution_epoch,
+            });
+        }
+        if state.mark_emission_end() {
+            emit!(EmissionEnded {
+                epoch: state.epoch_number,
+                total_mined: state.total_mined,
+            });
+        }
+
+        // Solution PDA closed by Anchor `close` constraint → rent to payee
+        Ok(())
+    }
+
+    /// Withdraw vested tokens.
+    ///
+    /// Calculates newly vested amount, then mints to recipient's token account.
+    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let clock = Clock::get()?;
+        let bump = ctx.accounts.mine_state.bump;
+
+        // ── Update vesting ──
+        let amount = take_unlocked(&mut ctx.accounts.vesting, clock.unix_timestamp)?;
+
+        // ── CPI: mint tokens to recipient ──
+        let seeds = &[b"mine_state".as_ref(), &[bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.recipient_token_account.to_account_info(),
+                    authority: ctx.accounts.mine_state.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Withdraw vested tokens into any CRB token account the miner names, such
+    /// as a vault owned by another program's PDA. The miner's signature is the
+    /// authorization; emits `VaultWithdrawal`.
+    pub fn withdraw_to_vault(ctx: Context<WithdrawToVault>) -> Result<()> {
+        let clock = Clock::get()?;
+        let amount = take_unlocked(&mut ctx.accounts.vesting, clock.unix_timestamp)?;
+
+        let seeds = &[b"mine_state".as_ref(), &[ctx.accounts.mine_state.bump]];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.mine_state.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+
+        emit!(VaultWithdrawal {
+            miner: ctx.accounts.miner.key(),
+            vault: ctx.accounts.vault.key(),
+            vault_owner: ctx.accounts.vault.owner,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Burn the caller's own tokens and release the same amount of supply-cap headroom.
+    pub fn burn(ctx: Context<BurnTokens>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        ctx.accounts.mine_state.record_burn(amount)?;
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.holder_token_account.to_account_info(),
+                    authority: ctx.accounts.holder.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        emit!(TokensBurned {
+            burner: ctx.accounts.holder.key(),
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Advance to the next epoch (crank only, or anyone with `permissionless_advance`).
+    ///
+    /// A permissionless caller who isn't a crank needs counter shards on, so the
+    /// count is checked on-chain, and is minted `advance_bonus` CRB when it passes
+    /// a token account (plus `mint` and `token_program`). With counter shards on, `remaining_accounts` must hold the epoch's
+    /// `SolutionCounter` PDAs in shard order (writable); `solution_count` must
+    /// equal their sum, and they are closed to the crank.
+    pub fn advance_epoch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AdvanceEpoch<'info>>,
+        solution_count: u64,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let state = &mut ctx.accounts.mine_state;
+
+        require!(!state.halted, ErrorCode::Halted);
+        require!(
+            state.is_crank(&ctx.accounts.crank.key()) || state.counter_shards > 0,
+            ErrorCode::UnverifiedSolutionCount
+        );
+
+        // ── Current epoch must have ended ──
+        require!(
+            !epoch_active(clock.unix_timestamp, state.epoch_end_time),
+            ErrorCode::EpochNotEnded
+        );
+
+        // ── Throttle: at most one advance per MIN_ADVANCE_INTERVAL ──
+        require!(
+            !advance_throttled(clock.unix_timestamp, state.last_advance_time),
+            ErrorCode::AdvanceThrottled
+        );
+        state.last_advance_time = clock.unix_timestamp;
+
+        // ── Sharded counter: the reported count must match the on-chain tally ──
+        if state.counter_shards > 0 {
+            let counted = tally_counter_shards(
+                ctx.remaining_accounts,
+                state.epoch_number,
+                state.counter_shards,
+                ctx.program_id,
+                &ctx.accounts.crank.to_account_info(),
+            )?;
+            require!(counted == solution_count, ErrorCode::SolutionCountMismatch);
+        } else {
+            // Defense in depth for a crank-reported count: bounded by the ceiling
+            // and by what could have landed in the epoch's span
+            require!(
+                plausible_solution_count(
+                    solution_count,
+                    state.epoch_end_time - state.epoch_start_time,
+                    state.max_reported_solutions
+                ),
+                ErrorCode::ImplausibleSolutionCount
+            );
+        }
+        state.counter_shards = state.next_counter_shards;
+
+        // ── Record solutions in this epoch ──
+        state.solutions_in_epoch = solution_count;
+        if let Some(history) = ctx.accounts.epoch_history.as_mut() {
+            history.push(EpochRecord {
+                epoch: state.epoch_number,
+                difficulty: state.difficulty,
+                solution_count,
+                start_time: state.epoch_start_time,
+            });
+        }
+
+        // ── Remember the ending epoch's words so the next ones avoid them ──
+        if state.recent_word_epochs > 0 {
+            let wordlist = ctx.accounts.wordlist.as_ref().ok_or(ErrorCode::WordlistRequired)?;
+            let ended = state.required_words(&state.challenge_seed, wordlist);
+            (state.recent_words, state.recent_words_age) = state.rolled_recent_words(&ended);
+        }
+
+        // ── Adjust difficulty (smoothed) ──
+        let best_hash = ctx.accounts.best_solution.as_ref().map(|best| best.hash);
+        let (ema, difficulty) = state.adjusted_difficulty(solution_count, clock.unix_timestamp, best_hash.as_ref());
+        state.difficulty_ema = ema;
+        state.difficulty = difficulty;
+        state.consecutive_empty_epochs = if solution_count == 0 {
+            state.consecutive_empty_epochs.saturating_add(1)
+        } else {
+            0
+        };
+
+        // ── New challenge seed ──
+        // A seed committed in the preview window is used as-is. Otherwise the
+        // latest slot hash isn't known (or grindable by the crank) until this
+        // transaction lands, so neither are the next epoch's words.
+        state.challenge_seed = match state.next_challenge_seed.take() {
+            Some(seed) => seed,
+            None => {
+                let slot_hash = {
+                    let data = ctx.accounts.slot_hashes.try_borrow_data()?;
+                    latest_slot_hash(&data).ok_or(ErrorCode::SlotHashNotFound)?
+                };
+                let seed_input = [
+                    state.challenge_seed.as_ref(),
+                    slot_hash.as_ref(),
+                    clock.unix_timestamp.to_le_bytes().as_ref(),
+                    clock.slot.to_le_bytes().as_ref(),
+                    solution_count.to_le_bytes().as_ref(),
+                ]
+                .concat();
+                keccak::hash(&seed_input).to_bytes()
+            }
+        };
+
+        // ── Advance epoch ──
+        state.epoch_number += 1;
+        state.epoch_start_time = clock.unix_timestamp;
+        state.epoch_end_time = clock.unix_timestamp + EPOCH_DURATION;
+        let bounty = state.advance_bounty;
+
+        // ── Pay the crank from the incentive pool (if funded) ──
+        let pool = &ctx.accounts.incentive_pool;
+        let rent_min = Rent::get()?.minimum_balance(0);
+        let payout = pool_payout(pool.lamports(), bounty, rent_min);
+        if payout > 0 {
+            let pool_bump = ctx.bumps.incentive_pool;
+            let seeds = &[b"incentive_pool".as_ref(), &[pool_bump]];
+            let signer_seeds = &[&seeds[..]];
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: pool.to_account_info(),
+                        to: ctx.accounts.crank.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                payout,
+            )?;
+        }
+
+        // ── Permissionless: mint the caller a CRB bonus, within the supply cap ──
+        let state = &ctx.accounts.mine_state;
+        let bonus = advance_bonus(state.permissionless_advance, state.advance_bonus, state.total_supply, state.max_supply);
+        if let (Some(to), Some(mint), Some(token_program), true) = (
+            &ctx.accounts.crank_token_account,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            bonus > 0,
+        ) {
+            let seeds = &[b"mine_state".as_ref(), &[state.bump]];
+            token::mint_to(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    MintTo {
+                        mint: mint.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: state.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                bonus,
+            )?;
+            let state = &mut ctx.accounts.mine_state;
+            state.total_supply = state.total_supply.checked_add(bonus).ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+
+        Ok(())
+    }
+
+    /// Create the EpochHistory ring that `advance_epoch` fills. Anyone may pay for it.
+    pub fn init_epoch_history(ctx: Context<InitEpochHistory>) -> Result<()> {
+        let history = &mut ctx.accounts.epoch_history;
+        history.head = 0;
+        history.len = 0;
+        history.records = [EpochRecord::default(); EPOCH_HISTORY_LEN];
+        history.bump = ctx.bumps.epoch_history;
+        Ok(())
+    }
+
+    /// Donate SOL to the incentive pool that pays cranks.
+    pub fn fund_incentive_pool(ctx: Context<FundIncentivePool>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: ctx.accounts.incentive_pool.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        emit!(PoolFunded {
+            funder: ctx.accounts.funder.key(),
+            amount,
+            balance: ctx.accounts.incentive_pool.lamports(),
+        });
+        Ok(())
+    }
+
+    /// Move any lamports `mine_state` holds above its rent-exempt minimum to
+    /// `treasury`. The program owns the account, so it debits it directly;
+    /// the reserve stays put. Governance only.
+    pub fn sweep_lamports(ctx: Context<SweepLamports>) -> Result<()> {
+        let state = ctx.accounts.mine_state.to_account_info();
+        let rent_min = Rent::get()?.minimum_balance(state.data_len());
+        let amount = sweepable_lamports(state.lamports(), rent_min);
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        **state.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.treasury.try_borrow_mut_lamports()? += amount;
+
+        emit!(LamportsSwept {
+            treasury: ctx.accounts.treasury.key(),
+            amount,
+            remaining: state.lamports(),
+        });
+        Ok(())
+    }
+
+    /// Point an unclaimed solution's reward and rent at `new_recipient`
+    /// (`Pubkey::default()` = back to the miner), e.g. from a hot key to cold
+    /// storage. The PDA seeds keep the original miner. Signed by the miner.
+    pub fn transfer_solution(ctx: Context<TransferSolution>, new_recipient: Pubkey) -> Result<()> {
+        let state = &ctx.accounts.mine_state;
+        let solution = &mut ctx.accounts.solution;
+        require!(
+            !claim_expired(state.epoch_number, solution.epoch, state.claim_expiry_epochs),
+            ErrorCode::ClaimExpired
+        );
+
+        let from = solution.transfer(new_recipient);
+        emit!(SolutionTransferred {
+            solution: solution.key(),
+            miner: solution.miner,
+            from,
+            to: solution.payee(),
+            epoch: solution.epoch,
+        });
+        Ok(())
+    }
+
+    /// Close an expired, unclaimed solution. Rent goes to caller as cleanup incentive.
+    pub fn close_expired(ctx: Context<CloseExpired>) -> Result<()> {
+        let current_epoch = ctx.accounts.mine_state.epoch_number;
+        let solution_epoch = ctx.accounts.solution.epoch;
+        let claim_expiry_epochs = ctx.accounts.mine_state.claim_expiry_epochs;
+
+        require!(
+            claim_expired(current_epoch, solution_epoch, claim_expiry_epochs),
+            ErrorCode::NotExpired
+        );
+
+        // Solution PDA closed by Anchor `close` constraint → rent to closer
+        Ok(())
+    }
+
+    /// Emit a `MineSummary` event with the most-polled state.
+    ///
+    /// Read-only; clients simulate this instead of decoding the raw `MineState` layout.
+    pub fn mine_summary(ctx: Context<ReadMineState>) -> Result<()> {
+        emit!(MineSummary::new(&ctx.accounts.mine_state));
+        Ok(())
+    }
+
+    /// Emit a `CostEstimate` event: what a submit costs and what comes back on claim.
+    pub fn cost_estimate(ctx: Context<ReadMineState>) -> Result<()> {
+        emit!(CostEstimate::new(&Rent::get()?, ctx.accounts.mine_state.submit_deposit));
+        Ok(())
+    }
+
+    /// Emit a `FeatureFlags` event: the account schema version and a bitfield
+    /// of the optional features this deployment has switched on.
+    pub fn feature_flags(ctx: Context<ReadMineState>) -> Result<()> {
+        emit!(FeatureFlags {
+            schema_version: ctx.accounts.mine_state.version,
+            flags: ctx.accounts.mine_state.feature_flags(),
+        });
+        Ok(())
+    }
+
+    /// Emit a `HealthStatus` event: which `HEALTH_*` invariants `mine_state`
+    /// fails (none when healthy). Reports rather than errors, for monitoring.
+    pub fn health_check(ctx: Context<ReadMineState>) -> Result<()> {
+        let (mint, _) = Pubkey::find_program_address(&[b"mint"], ctx.program_id);
+        let failed_checks = ctx.accounts.mine_state.health_failures(&mint);
+        emit!(HealthStatus {
+            healthy: failed_checks == 0,
+            failed_checks,
+        });
+        Ok(())
+    }
+
+    /// Emit a `DifficultyPreview` event: the difficulty `advance_epoch` would set
+    /// now for `solution_count` (and the optional best solution). Read-only.
+    pub fn preview_difficulty_adjustment(ctx: Context<PreviewDifficulty>, solution_count: u64) -> Result<()> {
+        let state = &ctx.accounts.mine_state;
+        let best_hash = ctx.accounts.best_solution.as_ref().map(|best| best.hash);
+        let (ema, difficulty) = state.adjusted_difficulty(solution_count, Clock::get()?.unix_timestamp, best_hash.as_ref());
+        emit!(DifficultyPreview {
+            epoch: state.epoch_number,
+            solution_count,
+            current_difficulty: state.difficulty,
+            difficulty,
+            difficulty_ema: ema,
+        });
+        Ok(())
+    }
+
+    /// Emit an `EpochStatus` event: whether submits are accepted right now and
+    /// seconds left in the epoch, by the cluster clock rather than the client's.
+    pub fn epoch_status(ctx: Context<ReadMineState>) -> Result<()> {
+        emit!(EpochStatus::new(&ctx.accounts.mine_state, Clock::get()?.unix_timestamp));
+        Ok(())
+    }
+
+    /// Emit a `HalvingCountdown` event: claims and (estimated) epochs until the next halving.
+    pub fn next_halving(ctx: Context<ReadMineState>) -> Result<()> {
+        emit!(HalvingCountdown::new(&ctx.accounts.mine_state));
+        Ok(())
+    }
+
+    /// Emit a `SolutionMiner` event naming whose reward a claim of this solution pays,
+    /// so third-party claimers and explorers can check before claiming.
+    pub fn solution_miner(ctx: Context<ReadSolution>) -> Result<()> {
+        emit!(SolutionMiner::new(ctx.accounts.solution.key(), &ctx.accounts.solution));
+        Ok(())
+    }
+
+    /// Emit an `EpochHistoryPage` event with the stored records for epochs
+    /// `start..start + count` (at most `MAX_HISTORY_PAGE`), oldest first. Only
+    /// the ring's last `EPOCH_HISTORY_LEN` epochs are stored, so backfills
+    /// older than that need the `advance_epoch` transactions themselves.
+    pub fn dump_epoch_history(ctx: Context<ReadEpochHistory>, start: u64, count: u64) -> Result<()> {
+        require!((1..=MAX_HISTORY_PAGE).contains(&count), ErrorCode::InvalidHistoryPage);
+        emit!(EpochHistoryPage::new(&ctx.accounts.epoch_history, start, count));
+        Ok(())
+    }
+
+    /// Emit a `DifficultyTrend` event: the current difficulty against the average of
+    /// the last `TREND_WINDOW` stored epochs, as `Rising`, `Falling` or `Stable`.
+    pub fn difficulty_trend(ctx: Context<ReadDifficultyTrend>) -> Result<()> {
+        emit!(DifficultyTrend::new(&ctx.accounts.mine_state, &ctx.accounts.epoch_history));
+        Ok(())
+    }
+
+    /// Emit a `TextCheck` event: whether `text` passes `submit_solution`'s text
+    /// checks (required words and `verify_text` rules) for the current epoch,
+    /// and the first rule it fails. Read-only; simulate it before hashing.
+    /// Fails with `WordDerivationFailed`, as submit does, when the wordlist
+    /// can't supply the difficulty's word count.
+    pub fn check_text(ctx: Context<ReadWords>, text: String) -> Result<()> {
+        let state = &ctx.accounts.mine_state;
+        let (required_words, result) = check_text_rules(state, &state.challenge_seed, &ctx.accounts.wordlist, text.as_bytes())?;
+        msg!("check_text: {:?}", result);
+        emit!(TextCheck {
+            epoch: state.epoch_number,
+            difficulty: state.difficulty,
+            required_words,
+            error: result.err(),
+        });
+        Ok(())
+    }
+
+    /// Emit a `WordOrder` event: this epoch's required words in the order the text must use them.
+    /// Also logged as plain text, so a simulation shows them without decoding the event.
+    /// Fails with `WordDerivationFailed`, as submit does, when the wordlist can't
+    /// supply the difficulty's word count.
+    pub fn word_order(ctx: Context<ReadWords>) -> Result<()> {
+        let state = &ctx.accounts.mine_state;
+        let rw = state.required_words(&state.challenge_seed, &ctx.accounts.wordlist);
+        require!(state.derivation_complete(&rw), ErrorCode::WordDerivationFailed);
+        let order = WordOrder::new(state.epoch_number, &rw);
+        msg!("epoch {} requires {} words: {}", order.epoch, order.words.len(), order.words.join(" "));
+        emit!(order);
+        Ok(())
+    }
+
+    /// Propose a new crank authority. Takes effect once the new address calls
+    /// `accept_crank_authority`; a later call replaces the pending proposal. Governance only.
+    pub fn set_crank_authority(ctx: Context<SetCrankAuthority>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.mine_state.pending_crank_authority = Some(new_authority);
+        Ok(())
+    }
+
+    /// Complete a crank authority transfer. Must be signed by the pending authority.
+    pub fn accept_crank_authority(ctx: Context<AcceptCrankAuthority>) -> Result<()> {
+        let state = &mut ctx.accounts.mine_state;
+        state.crank_authority = ctx.accounts.new_authority.key();
+        state.pending_crank_authority = None;
+        Ok(())
+    }
+
+    /// Authorize an extra crank operator to call `advance_epoch`. Governance only.
+    pub fn add_crank(ctx: Context<UpdateConfig>, crank: Pubkey) -> Result<()> {
+        ctx.accounts.mine_state.add_crank(crank)
+    }
+
+    /// Revoke a crank operator added with `add_crank`. Governance only.
+    pub fn remove_crank(ctx: Context<UpdateConfig>, crank: Pubkey) -> Result<()> {
+        ctx.accounts.mine_state.remove_crank(&crank)
+    }
+
+    /// Block `miner` from submitting: creates its `BlockedMiner` PDA. Crank authority only.
+    ///
+    /// Solutions the miner already submitted can still be claimed.
+    pub fn block_miner(ctx: Context<BlockMiner>, miner: Pubkey) -> Result<()> {
+        let blocked = &mut ctx.accounts.blocked_miner;
+        blocked.miner = miner;
+        blocked.bump = ctx.bumps.blocked_miner;
+        Ok(())
+    }
+
+    /// Lift a block: closes the miner's `BlockedMiner` PDA. Crank authority only.
+    pub fn unblock_miner(_ctx: Context<UnblockMiner>, _miner: Pubkey) -> Result<()> {
+        // BlockedMiner PDA closed by Anchor `close` constraint → rent to the authority
+        Ok(())
+    }
+
+    /// Propose a new governance key. Takes effect once the new address calls
+    /// `accept_governance`; a later call replaces the pending proposal. Governance only.
+    pub fn set_governance(ctx: Context<SetGovernance>, new_governance: Pubkey) -> Result<()> {
+        ctx.accounts.mine_state.pending_governance = Some(new_governance);
+        Ok(())
+    }
+
+    /// Complete a governance transfer. Must be signed by the pending governance key.
+    pub fn accept_governance(ctx: Context<AcceptGovernance>) -> Result<()> {
+        let state = &mut ctx.accounts.mine_state;
+        state.governance = ctx.accounts.new_governance.key();
+        state.pending_governance = None;
+        Ok(())
+    }
+
+    /// Withdraw a pending governance transfer. Current governance only.
+    pub fn cancel_governance_transfer(ctx: Context<SetGovernance>) -> Result<()> {
+        let state = &mut ctx.accounts.mine_state;
+        require!(state.pending_governance.is_some(), ErrorCode::NoPendingAuthority);
+        state.pending_governance = None;
+        Ok(())
+    }
+
+    /// Break glass: stop `submit_solution`, `claim` and `advance_epoch`. Guardian only.
+    ///
+    /// `close_expired` keeps working so solution rent is never trapped.
+    pub fn emergency_halt(ctx: Context<GuardianAction>) -> Result<()> {
+        ctx.accounts.mine_state.halted = true;
+        Ok(())
+    }
+
+    /// Lift an emergency halt. Guardian only.
+    pub fn resume(ctx: Context<GuardianAction>) -> Result<()> {
+        ctx.accounts.mine_state.halted = false;
+        Ok(())
+    }
+
+    /// Withdraw a pending crank authority transfer. Governance only.
+    pub fn cancel_crank_transfer(ctx: Context<SetCrankAuthority>) -> Result<()> {
+        let state = &mut ctx.accounts.mine_state;
+        require!(state.pending_crank_authority.is_some(), ErrorCode::NoPendingAuthority);
+        state.pending_crank_authority = None;
+        Ok(())
+    }
+
+    /// Reset mining state. Governance only. For re-initialization.
+    pub fn reset_state(ctx: Context<ResetState>) -> Result<()> {
+        let clock = Clock::get()?;
+        let state = &mut ctx.accounts.mine_state;
+
+        let seed_input = [
+            clock.slot.to_le_bytes().as_ref(),
+            clock.unix_timestamp.to_le_bytes().as_ref(),
+            state.key().as_ref(),
+        ]
+        .concat();
+        let challenge_seed = keccak::hash(&seed_input).to_bytes();
+        state.reset(challenge_seed, clock.unix_timestamp);
+        Ok(())
+    }
+
+    /// Grow a MineState written by an older program to the current layout,
+    /// fill the new fields with defaults and stamp `version`. Governance only;
+    /// on accounts that predate `governance` the crank authority may migrate,
+    /// and becomes governance. Governance pays any extra rent.
+    pub fn migrate_mine_state(ctx: Context<MigrateMineState>) -> Result<()> {
+        let info = ctx.accounts.mine_state.to_account_info();
+        let space = 8 + MineState::INIT_SPACE;
+        if info.data_len() < space {
+            let shortfall = Rent::get()?.minimum_balance(space).saturating_sub(info.lamports());
+            if shortfall > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.governance.to_account_info(),
+                            to: info.clone(),
+                        },
+                    ),
+                    shortfall,
+                )?;
+            }
+            // New bytes read as zero, so an unversioned account decodes as version 0
+            info.realloc(space, true)?;
+        }
+
+        let mut state = MineState::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(state.version < SCHEMA_VERSION, ErrorCode::AlreadyMigrated);
+        let signer = ctx.accounts.governance.key();
+        require!(
+            signer == state.governance
+                || (state.governance == Pubkey::default() && signer == state.crank_authority),
+            ErrorCode::Unauthorized
+        );
+        let from = state.version;
+        state.migrate();
+        state.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        emit!(MineStateMigrated { from, to: SCHEMA_VERSION });
+        Ok(())
+    }
+
+    /// Create the on-chain wordlist. Governance only.
+    ///
+    /// `words = None` seeds the account with the compiled default `WORDLIST`.
+    /// Custom lists too large for one transaction can be completed with `append_words`.
+    pub fn init_wordlist(ctx: Context<InitWordlist>, words: Option<Vec<String>>) -> Result<()> {
+        let wordlist = &mut ctx.accounts.wordlist;
+        wordlist.count = 0;
+        wordlist.data = Vec::new();
+        wordlist.bump = ctx.bumps.wordlist;
+
+        match words {
+            Some(list) => {
+                for word in list.iter() {
+                    wordlist.push(word.as_bytes())?;
+                }
+                wordlist.category_ends = [0; words::CATEGORY_COUNT];
+            }
+            None => {
+                for word in words::WORDLIST.iter() {
+                    wordlist.push(word.as_bytes())?;
+                }
+                wordlist.category_ends = words::DEFAULT_CATEGORY_ENDS;
+            }
+        }
+        Ok(())
+    }
+
+    /// Append words to the on-chain wordlist. Governance only.
+    pub fn append_words(ctx: Context<UpdateWordlist>, words: Vec<String>) -> Result<()> {
+        let wordlist = &mut ctx.accounts.wordlist;
+        for word in words.iter() {
+            wordlist.push(word.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Set the wordlist's category ranges (exclusive end index per category).
+    /// All zeros disables category-aware derivation. Governance only.
+    pub fn set_wordlist_categories(
+        ctx: Context<UpdateWordlist>,
+        category_ends: [u16; words::CATEGORY_COUNT],
+    ) -> Result<()> {
+        let wordlist = &mut ctx.accounts.wordlist;
+        require!(
+            words::is_valid_category_ends(&category_ends, wordlist.count as usize),
+            ErrorCode::InvalidCategories
+        );
+        wordlist.category_ends = category_ends;
+        Ok(())
+    }
+
+    /// Set the claim window in epochs (at least `MIN_CLAIM_EXPIRY_EPOCHS`). Governance only.
+    ///
+    /// Applies retroactively: shortening it can expire solutions that were
+    /// already submitted under the longer window.
+    pub fn set_claim_expiry(ctx: Context<UpdateConfig>, epochs: u64) -> Result<()> {
+        require!(epochs >= MIN_CLAIM_EXPIRY_EPOCHS, ErrorCode::InvalidClaimExpiry);
+        ctx.accounts.mine_state.claim_expiry_epochs = epochs;
+        Ok(())
+    }
+
+    /// Set how many extra difficulty bits the epoch's best hash may add (0 disables,
+    /// at most `MAX_MIN_HASH_WEIGHT`). Governance only.
+    pub fn set_min_hash_weight(ctx: Context<UpdateConfig>, weight: u8) -> Result<()> {
+        require!(weight <= MAX_MIN_HASH_WEIGHT, ErrorCode::InvalidMinHashWeight);
+        ctx.accounts.mine_state.min_hash_weight = weight;
+        Ok(())
+    }
+
+    /// Set the difficulty floor and ceiling and the largest per-epoch step.
+    /// Applied from the next `advance_epoch`. Governance only.
+    pub fn set_difficulty_bounds(ctx: Context<UpdateConfig>, min: u64, max: u64, max_adj: u64) -> Result<()> {
+        let bounds = DifficultyBounds { min, max, max_adj };
+        require!(bounds.is_valid(), ErrorCode::InvalidDifficultyBounds);
+        let state = &mut ctx.accounts.mine_state;
+        state.min_difficulty = min;
+        state.max_difficulty = max;
+        state.max_difficulty_adj = max_adj;
+        Ok(())
+    }
+
+    /// Set the difficulties past which one more word is required (3 words up to
+    /// the first, 8 past the last). Must be strictly increasing. Governance only.
+    pub fn set_word_count_thresholds(
+        ctx: Context<UpdateConfig>,
+        thresholds: [u64; words::WORD_COUNT_THRESHOLDS],
+    ) -> Result<()> {
+        require!(words::is_valid_word_count_thresholds(&thresholds), ErrorCode::InvalidWordCountThresholds);
+        ctx.accounts.mine_state.word_count_thresholds = thresholds;
+        Ok(())
+    }
+
+    /// Set the anti-spam deposit each submit locks in its Solution PDA.
+    /// Refunded on claim; paid to the closer by `close_expired`. Governance only.
+    pub fn set_submit_deposit(ctx: Context<UpdateConfig>, lamports: u64) -> Result<()> {
+        ctx.accounts.mine_state.submit_deposit = lamports;
+        Ok(())
+    }
+
+    /// Enable or disable cross-miner text dedup. Governance only.
+    ///
+    /// While enabled, each submit must create the `TextSeen` PDA for its text,
+    /// so a second submit of the same text in the same epoch fails.
+    pub fn set_dedup_texts(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        ctx.accounts.mine_state.dedup_texts = enabled;
+        Ok(())
+    }
+
+    /// Set how many solutions one miner may submit per epoch (1 to `MAX_SOLUTIONS_PER_MINER`).
+    /// Governance only.
+    pub fn set_max_solutions_per_miner(ctx: Context<UpdateConfig>, count: u8) -> Result<()> {
+        require!(
+            (1..=MAX_SOLUTIONS_PER_MINER).contains(&count),
+            ErrorCode::InvalidSolutionCap
+        );
+        ctx.accounts.mine_state.max_solutions_per_miner = count;
+        Ok(())
+    }
+
+    /// Boost rewards for solutions from the first `epochs` epochs: `multiplier_bps`
+    /// (10000 = 1×, at most `MAX_BOOST_BPS`) at epoch 0, decaying linearly to 1×.
+    /// Governance only.
+    pub fn set_early_boost(ctx: Context<UpdateConfig>, epochs: u64, multiplier_bps: u16) -> Result<()> {
+        require!(
+            (BPS_ONE..=MAX_BOOST_BPS).contains(&multiplier_bps),
+            ErrorCode::InvalidBoost
+        );
+        let state = &mut ctx.accounts.mine_state;
+        state.boost_epochs = epochs;
+        state.boost_multiplier_bps = multiplier_bps;
+        Ok(())
+    }
+
+    /// Normalize the solution count to the intended epoch length when `advance_epoch`
+    /// runs late, before the difficulty step. Governance only.
+    pub fn set_normalize_overrun(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        ctx.accounts.mine_state.normalize_overrun = enabled;
+        Ok(())
+    }
+
+    /// Choose what counts as a required word's boundary: letters only
+    /// (`BOUNDARY_ALPHA`, the default), letters and digits, or whitespace only.
+    /// Governance only.
+    pub fn set_boundary_mode(ctx: Context<UpdateConfig>, mode: u8) -> Result<()> {
+        require!(verify::is_valid_boundary_mode(mode), ErrorCode::InvalidBoundaryMode);
+        ctx.accounts.mine_state.boundary_mode = mode;
+        Ok(())
+    }
+
+    /// Reject texts whose digits exceed `pct` percent of the bytes (0 = no limit;
+    /// new deployments start at 5, migrated ones at 0). Governance only.
+    pub fn set_max_digit_pct(ctx: Context<UpdateConfig>, pct: u8) -> Result<()> {
+        let ext = TextRulesExt { max_digit_pct: pct, ..ctx.accounts.mine_state.ext_rules() };
+        require!(ext.is_valid(), ErrorCode::InvalidTextRules);
+        ctx.accounts.mine_state.set_ext_rules(&ext);
+        Ok(())
+    }
+
+    /// Require at least `count` distinct sentence endings (`.`, `!`, `?`) in a
+    /// text, 0–3 (0 = off). Governance only.
+    pub fn set_min_ending_variety(ctx: Context<UpdateConfig>, count: u8) -> Result<()> {
+        let ext = TextRulesExt { min_ending_variety: count, ..ctx.accounts.mine_state.ext_rules() };
+        require!(ext.is_valid(), ErrorCode::InvalidTextRules);
+        ctx.accounts.mine_state.set_ext_rules(&ext);
+        Ok(())
+    }
+
+    /// Require double quotes (`"`) in texts to come in pairs. Governance only.
+    pub fn set_require_balanced_quotes(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        let ext = TextRulesExt { require_balanced_quotes: enabled, ..ctx.accounts.mine_state.ext_rules() };
+        require!(ext.is_valid(), ErrorCode::InvalidTextRules);
+        ctx.accounts.mine_state.set_ext_rules(&ext);
+        Ok(())
+    }
+
+    /// Require each sentence's first letter to be a capital, tolerating
+    /// `tolerance` lowercase starts (at most `MAX_LOWERCASE_START_TOLERANCE`). Governance only.
+    pub fn set_require_capitals(ctx: Context<UpdateConfig>, enabled: bool, tolerance: u8) -> Result<()> {
+        let ext = TextRulesExt {
+            require_capitals: enabled,
+            lowercase_start_tolerance: tolerance,
+            ..ctx.accounts.mine_state.ext_rules()
+        };
+        require!(ext.is_valid(), ErrorCode::InvalidTextRules);
+        ctx.accounts.mine_state.set_ext_rules(&ext);
+        Ok(())
+    }
+
+    /// Pay `per_word_bps` more per required word beyond the minimum the solution
+    /// was mined under (0 = off, at most `MAX_WORD_REWARD_BPS`). Governance only.
+    pub fn set_word_reward(ctx: Context<UpdateConfig>, per_word_bps: u16) -> Result<()> {
+        require!(per_word_bps <= MAX_WORD_REWARD_BPS, ErrorCode::InvalidWordReward);
+        ctx.accounts.mine_state.word_reward_bps = per_word_bps;
+        Ok(())
+    }
+
+    /// Refuse submits in the last `cutoff_bps` of each epoch (0 = off, at most
+    /// `MAX_SUBMIT_CUTOFF_BPS`), spreading them out ahead of the end. Governance only.
+    pub fn set_submit_cutoff(ctx: Context<UpdateConfig>, cutoff_bps: u16) -> Result<()> {
+        require!(cutoff_bps <= MAX_SUBMIT_CUTOFF_BPS, ErrorCode::InvalidSubmitCutoff);
+        ctx.accounts.mine_state.submit_cutoff_bps = cutoff_bps;
+        Ok(())
+    }
+
+    /// Enable or disable the repeat-hash check. Governance only.
+    ///
+    /// While enabled, each submit must pass the miner's `LastHash` PDA and is
+    /// rejected if its hash equals the one that miner submitted last.
+    pub fn set_reject_repeat_hash(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        ctx.accounts.mine_state.reject_repeat_hash = enabled;
+        Ok(())
+    }
+
+    /// Set the vowel band (percent of letters) and space band (percent of bytes)
+    /// texts must fall in; each needs `low < high <= 100`. Governance only.
+    pub fn set_ratio_bounds(
+        ctx: Context<UpdateConfig>,
+        min_vowel_pct: u8,
+        max_vowel_pct: u8,
+        min_space_pct: u8,
+        max_space_pct: u8,
+    ) -> Result<()> {
+        let ext = TextRulesExt {
+            min_vowel_pct,
+            max_vowel_pct,
+            min_space_pct,
+            max_space_pct,
+            ..ctx.accounts.mine_state.ext_rules()
+        };
+        require!(ext.is_valid(), ErrorCode::InvalidTextRules);
+        ctx.accounts.mine_state.set_ext_rules(&ext);
+        Ok(())
+    }
+
+    /// Require `min_unique_bytes` distinct bytes per text (1–128) and
+    /// `min_sentence_unique_bytes` per sentence (0 = off, at most the text floor).
+    /// Governance only.
+    pub fn set_byte_diversity(ctx: Context<UpdateConfig>, min_unique_bytes: u8, min_sentence_unique_bytes: u8) -> Result<()> {
+        let ext = TextRulesExt {
+            min_unique_bytes,
+            min_sentence_unique_bytes,
+            ..ctx.accounts.mine_state.ext_rules()
+        };
+        require!(ext.is_valid(), ErrorCode::InvalidTextRules);
+        ctx.accounts.mine_state.set_ext_rules(&ext);
+        Ok(())
+    }
+
+    /// Require each required word once more per `bytes` bytes of text past the
+    /// 256-byte minimum (0 = off, else at least `MIN_BYTES_PER_EXTRA_MATCH`). Governance only.
+    pub fn set_bytes_per_extra_match(ctx: Context<UpdateConfig>, bytes: u16) -> Result<()> {
+        let ext = TextRulesExt { bytes_per_extra_match: bytes, ..ctx.accounts.mine_state.ext_rules() };
+        require!(ext.is_valid(), ErrorCode::InvalidTextRules);
+        ctx.accounts.mine_state.set_ext_rules(&ext);
+        Ok(())
+    }
+
+    /// Step difficulty down by just 1 for an empty epoch until `epochs` empty
+    /// epochs in a row (0 = off: every empty epoch drops by the full `max_adj`).
+    /// Governance only.
+    pub fn set_empty_epoch_grace(ctx: Context<UpdateConfig>, epochs: u8) -> Result<()> {
+        ctx.accounts.mine_state.empty_epoch_grace = epochs;
+        Ok(())
+    }
+
+    /// Only allow claims once a solution's epoch has been advanced, not merely
+    /// ended by the clock (a stalled crank then also stalls claims). Governance only.
+    pub fn set_claim_requires_advance(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        ctx.accounts.mine_state.claim_requires_advance = enabled;
+        Ok(())
+    }
+
+    /// Set the share of a claim (basis points, at most `MAX_CLAIM_TIP_BPS`) minted
+    /// to a third party who claims for a miner. Governance only.
+    pub fn set_claim_tip_bps(ctx: Context<UpdateConfig>, bps: u16) -> Result<()> {
+        require!(bps <= MAX_CLAIM_TIP_BPS, ErrorCode::InvalidClaimTip);
+        ctx.accounts.mine_state.claim_tip_bps = bps;
+        Ok(())
+    }
+
+    /// Reject new submits once `emission_ended` is set (mining then earns nothing).
+    /// Governance only.
+    pub fn set_stop_after_emission(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        ctx.accounts.mine_state.stop_after_emission = enabled;
+        Ok(())
+    }
+
+    /// Set the number of `SolutionCounter` shards (0 disables, at most `MAX_COUNTER_SHARDS`).
+    /// Takes effect from the next epoch so one epoch is never counted two ways.
+    /// Governance only.
+    pub fn set_counter_shards(ctx: Context<UpdateConfig>, shards: u8) -> Result<()> {
+        require!(shards <= MAX_COUNTER_SHARDS, ErrorCode::InvalidCounterShards);
+        ctx.accounts.mine_state.next_counter_shards = shards;
+        Ok(())
+    }
+
+    /// Set the lamports paid from the incentive pool to each `advance_epoch` caller.
+    /// Governance only.
+    pub fn set_advance_bounty(ctx: Context<UpdateConfig>, lamports: u64) -> Result<()> {
+        ctx.accounts.mine_state.advance_bounty = lamports;
+        Ok(())
+    }
+
+    /// Let anyone call `advance_epoch` (non-crank callers need counter shards on)
+    /// and mint each caller `bonus` CRB base units, at most `initial_reward / 10`,
+    /// counted against the supply cap. Governance only.
+    pub fn set_permissionless_advance(ctx: Context<UpdateConfig>, enabled: bool, bonus: u64) -> Result<()> {
+        let state = &mut ctx.accounts.mine_state;
+        require!(bonus <= state.initial_reward / MAX_ADVANCE_BONUS_DIVISOR, ErrorCode::InvalidAdvanceBonus);
+        state.permissionless_advance = enabled;
+        state.advance_bonus = bonus;
+        Ok(())
+    }
+
+    /// Scale the per-epoch solution target with the network: `TARGET_SOLUTIONS`
+    /// plus `per_miner_bps / 10000` per reported unique miner, up to `max_target`
+    /// (at most `MAX_TARGET_SOLUTIONS`). `per_miner_bps = 0` turns it off. Governance only.
+    pub fn set_target_scaling(ctx: Context<UpdateConfig>, per_miner_bps: u16, max_target: u64) -> Result<()> {
+        require!(
+            (TARGET_SOLUTIONS..=MAX_TARGET_SOLUTIONS).contains(&max_target),
+            ErrorCode::InvalidTargetScaling
+        );
+        let state = &mut ctx.accounts.mine_state;
+        state.target_per_miner_bps = per_miner_bps;
+        state.max_target_solutions = max_target;
+        Ok(())
+    }
+
+    /// Refuse crank-reported solution counts above `max` (0 = off, else at least
+    /// `MAX_TARGET_SOLUTIONS`). Counts checked by counter shards are exempt. Governance only.
+    pub fn set_max_reported_solutions(ctx: Context<UpdateConfig>, max: u64) -> Result<()> {
+        require!(max == 0 || max >= MAX_TARGET_SOLUTIONS, ErrorCode::InvalidReportCeiling);
+        ctx.accounts.mine_state.max_reported_solutions = max;
+        Ok(())
+    }
+
+    /// Keep each epoch's required words out of the next `epochs` epochs'
+    /// (0 = off, at most `MAX_RECENT_WORD_EPOCHS`). `advance_epoch` then needs
+    /// the wordlist. Clears the recent set. Governance only.
+    pub fn set_recent_word_epochs(ctx: Context<UpdateConfig>, epochs: u8) -> Result<()> {
+        require!(epochs <= MAX_RECENT_WORD_EPOCHS, ErrorCode::InvalidRecentWordEpochs);
+        let state = &mut ctx.accounts.mine_state;
+        state.recent_word_epochs = epochs;
+        state.recent_words_age = 0;
+        state.recent_words = [0; words::WORD_BITMAP_LEN];
+        Ok(())
+    }
+
+    /// Enable or disable chain mode. Governance only.
+    ///
+    /// While enabled, every submit hash must include the hash of the miner's
+    /// most recently claimed solution (stored in their MinerConfig).
+    pub fn set_chain_mode(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        ctx.accounts.mine_state.chain_mode = enabled;
+        Ok(())
+    }
+
+    /// Replace the text verification rules. Governance only.
+    pub fn set_text_rules(ctx: Context<UpdateConfig>, rules: TextRules) -> Result<()> {
+        require!(rules.is_valid(), ErrorCode::InvalidTextRules);
+        ctx.accounts.mine_state.text_rules = rules;
+        Ok(())
+    }
+
+    /// Record how many distinct miners submitted in the last epoch; with target
+    /// scaling on, the difficulty target grows with it. Crank or operator.
+    pub fn report_network_growth(ctx: Context<CrankAction>, unique_miners: u64) -> Result<()> {
+        let state = &mut ctx.accounts.mine_state;
+        state.unique_miners = unique_miners;
+        emit!(NetworkGrowth {
+            epoch: state.epoch_number,
+            unique_miners,
+            target_solutions: state.effective_target(),
+        });
+        Ok(())
+    }
+
+    /// Fix the next epoch's challenge seed early so `next_challenge_preview` can show
+    /// its words. Crank or operator; only within `preview_lead_seconds` of the epoch end,
+    /// once per epoch. The seed mixes in the latest slot hash, as in `advance_epoch`.
+    pub fn commit_next_seed(ctx: Context<CommitNextSeed>) -> Result<()> {
+        let clock = Clock::get()?;
+        let state = &mut ctx.accounts.mine_state;
+        require!(
+            in_preview_window(clock.unix_timestamp, state.epoch_end_time, state.preview_lead_seconds),
+            ErrorCode::PreviewWindowClosed
+        );
+        require!(state.next_challenge_seed.is_none(), ErrorCode::NextSeedCommitted);
+
+        let slot_hash = {
+            let data = ctx.accounts.slot_hashes.try_borrow_data()?;
+            latest_slot_hash(&data).ok_or(ErrorCode::SlotHashNotFound)?
+        };
+        let next_epoch = state.epoch_number + 1;
+        state.next_challenge_seed = Some(
+            keccak::hashv(&[&state.challenge_seed, &slot_hash, &next_epoch.to_le_bytes()]).to_bytes(),
+        );
+        Ok(())
+    }
+
+    /// Set how long before the epoch end the next seed may be committed and
+    /// previewed (0 disables, at most `EPOCH_DURATION`). Governance only.
+    pub fn set_preview_lead(ctx: Context<UpdateConfig>, seconds: i64) -> Result<()> {
+        require!((0..=EPOCH_DURATION).contains(&seconds), ErrorCode::InvalidPreviewLead);
+        ctx.accounts.mine_state.preview_lead_seconds = seconds;
+        Ok(())
+    }
+
+    /// Emit a `WordOrder` event for the next epoch from the committed next seed.
+    ///
+    /// Only inside the preview window. The word count follows the current
+    /// difficulty and can change if `advance_epoch` moves it across a tier.
+    /// Fails with `WordDerivationFailed` when the wordlist can't supply that count.
+    pub fn next_challenge_preview(ctx: Context<ReadWords>) -> Result<()> {
+        let clock = Clock::get()?;
+        let state = &ctx.accounts.mine_state;
+        require!(
+            in_preview_window(clock.unix_timestamp, state.epoch_end_time, state.preview_lead_seconds),
+            ErrorCode::PreviewWindowClosed
+        );
+        let seed = state.next_challenge_seed.ok_or(ErrorCode::PreviewWindowClosed)?;
+        // The words `advance_epoch` will add to the recent set
+        let ended = state.required_words(&state.challenge_seed, &ctx.accounts.wordlist);
+        let (recent, _) = state.rolled_recent_words(&ended);
+        let rw = state.required_words_avoiding(&seed, &recent, &ctx.accounts.wordlist);
+        require!(state.derivation_complete(&rw), ErrorCode::WordDerivationFailed);
+        emit!(WordOrder::new(state.epoch_number + 1, &rw));
+        Ok(())
+    }
+
+    /// Emergency: push the current epoch's end time forward so submissions stay open
+    /// while the crank is down. Governance only.
+    ///
+    /// The epoch can never be stretched past `MAX_EPOCH_SPAN` from its start.
+    pub fn extend_current_epoch(ctx: Context<UpdateConfig>, extra_seconds: i64) -> Result<()> {
+        let state = &mut ctx.accounts.mine_state;
+        state.epoch_end_time = extended_epoch_end(state.epoch_start_time, state.epoch_end_time, extra_seconds)
+            .ok_or(ErrorCode::InvalidExtension)?;
+
+        emit!(EpochExtended {
+            epoch: state.epoch_number,
+            epoch_end_time: state.epoch_end_time,
+        });
+        Ok(())
+    }
+
+    /// Replace the text verification rules with a named preset
+    /// (0 = standard, 1 = strict, 2 = lenient, 3 = testing). Governance only.
+    pub fn set_verification_preset(ctx: Context<UpdateConfig>, preset: u8) -> Result<()> {
+        let rules = TextRules::preset(preset).ok_or(ErrorCode::InvalidPreset)?;
+        ctx.accounts.mine_state.text_rules = rules;
+        Ok(())
+    }
+
+    /// Create token metadata via Metaplex, or update its name, symbol and uri if
+    /// the metadata account already exists, so deploy scripts can re-run. Governance only.
+    pub fn create_metadata(
+        ctx: Context<CreateMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        let bump = ctx.accounts.mine_state.bump;
+        let seeds = &[b"mine_state".as_ref(), &[bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let data_v2 = mpl_token_metadata::types::DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        };
+
+        let metadata = &ctx.accounts.metadata;
+        if metadata_exists(metadata.owner, metadata.data_is_empty()) {
+            mpl_token_metadata::instructions::UpdateMetadataAccountV2Cpi::new(
+                &ctx.accounts.token_metadata_program.to_account_info(),
+                mpl_token_metadata::instructions::UpdateMetadataAccountV2CpiAccounts {
+                    metadata: &metadata.to_account_info(),
+                    update_authority: &ctx.accounts.mine_state.to_account_info(),
+                },
+                mpl_token_metadata::instructions::UpdateMetadataAccountV2InstructionArgs {
+                    data: Some(data_v2),
+                    new_update_authority: None,
+                    primary_sale_happened: None,
+                    is_mutable: None,
+                },
+            ).invoke_signed(signer_seeds)?;
+            return Ok(());
+        }
+
+        let metadata_accounts = mpl_token_metadata::instructions::CreateMetadataAccountV3CpiAccounts {
+            metadata: &ctx.accounts.metadata.to_account_info(),
+            mint: &ctx.accounts.mint.to_account_info(),
+            mint_authority: &ctx.accounts.mine_state.to_account_info(),
+            payer: &ctx.accounts.payer.to_account_info(),
+            update_authority: (&ctx.accounts.mine_state.to_account_info(), true),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            rent: Some(&ctx.accounts.rent.to_account_info()),
+        };
+
+        mpl_token_metadata::instructions::CreateMetadataAccountV3Cpi::new(
+            &ctx.accounts.token_metadata_program.to_account_info(),
+            metadata_accounts,
+            mpl_token_metadata::instructions::CreateMetadataAccountV3InstructionArgs {
+                data: data_v2,
+                is_mutable: true,
+                collection_details: None,
+            },
+        ).invoke_signed(signer_seeds)?;
+
+        Ok(())
+    }
+}
+
+// ============================================================
+// Helpers
+// ============================================================
+
+/// Whether the metadata account was already created: Metaplex owns it and it holds data.
+fn metadata_exists(owner: &Pubkey, data_is_empty: bool) -> bool {
+    *owner == mpl_token_metadata::ID && !data_is_empty
+}
+
+/// Shared body of `submit_solution` and `reveal_solution`.
+///
+/// mine_state is READ-ONLY — zero write-lock contention.
+fn process_submit(
+    accounts: &mut SubmitSolution,
+    bumps: &SubmitSolutionBumps,
+    text: &[u8],
+    nonce: u64,
+    recipient: Pubkey,
+    slot: u64,
+    index: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    // ── Read state (mine_state is read-only, no write lock) ──
+    let challenge_seed = accounts.mine_state.challenge_seed;
+    let difficulty = accounts.mine_state.difficulty;
+    let epoch_number = accounts.mine_state.epoch_number;
+    let epoch_start_time = accounts.mine_state.epoch_start_time;
+    let epoch_end_time = accounts.mine_state.epoch_end_time;
+    let total_supply = accounts.mine_state.total_supply;
+    let max_supply = accounts.mine_state.max_supply;
+    let chain_mode = accounts.mine_state.chain_mode;
+    let submit_deposit = accounts.mine_state.submit_deposit;
+    let dedup_texts = accounts.mine_state.dedup_texts;
+    let max_solutions = accounts.mine_state.max_solutions_per_miner;
+    let counter_shards = accounts.mine_state.counter_shards;
+    let reject_repeat_hash = accounts.mine_state.reject_repeat_hash;
+
+    require!(!accounts.mine_state.halted, ErrorCode::Halted);
+    require!(!is_blocked(&accounts.blocked_miner), ErrorCode::MinerBlocked);
+    require!(
+        !(accounts.mine_state.emission_ended && accounts.mine_state.stop_after_emission),
+        ErrorCode::EmissionEnded
+    );
+    require!(index < max_solutions as u64, ErrorCode::SolutionIndexOutOfRange);
+
+    // ── Epoch must be active ──
+    require!(
+        epoch_active(clock.unix_timestamp, epoch_end_time),
+        ErrorCode::EpochEnded
+    );
+    require!(
+        !in_submit_cutoff(clock.unix_timestamp, epoch_start_time, epoch_end_time, accounts.mine_state.submit_cutoff_bps),
+        ErrorCode::SubmitCutoff
+    );
+
+    // ── Supply cap ──
+    require!(total_supply < max_supply, ErrorCode::MaxSupplyReached);
+
+    // ── Derive required words and verify text constraints ──
+    let required_word_count = verify_required_words(&accounts.mine_state, &challenge_seed, &accounts.wordlist, text)?;
+
+    // ── Text dedup: the TextSeen PDA init already failed if this text was seen this epoch ──
+    match accounts.text_seen.as_mut() {
+        Some(seen) => seen.bump = bumps.text_seen.ok_or(ErrorCode::TextSeenRequired)?,
+        None => require!(!dedup_texts, ErrorCode::TextSeenRequired),
+    }
+
+    // ── Sharded solution counter ──
+    match accounts.solution_counter.as_mut() {
+        Some(counter) => {
+            counter.count = counter.count.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+            counter.bump = bumps.solution_counter.ok_or(ErrorCode::CounterRequired)?;
+        }
+        None => require!(counter_shards == 0, ErrorCode::CounterRequired),
+    }
+
+    // ── Chain mode: hash must commit to the miner's last claimed solution ──
+    let prior_hash = if chain_mode {
+        let config = accounts
+            .miner_config
+            .as_ref()
+            .ok_or(ErrorCode::MinerConfigRequired)?;
+        Some(config.prior_hash)
+    } else {
+        None
+    };
+
+    // ── Recent slot hash: a solution can't be ground before that slot exists ──
+    let slot_hash = {
+        let data = accounts.slot_hashes.try_borrow_data()?;
+        find_slot_hash(&data, slot).ok_or(ErrorCode::SlotHashNotFound)?
+    };
+
+    // ── Compute hash ──
+    let miner_key = accounts.miner.key();
+    let hash_bytes = solution_hash(
+        &challenge_seed,
+        &slot_hash,
+        prior_hash.as_ref(),
+        &miner_key,
+        text,
+        nonce,
+    );
+
+    // ── Verify PoW difficulty ──
+    require!(
+        check_difficulty(&hash_bytes, difficulty),
+        ErrorCode::InsufficientDifficulty
+    );
+
+    // ── Repeat-hash check: the miner's LastHash must not already hold this hash ──
+    match accounts.last_hash.as_mut() {
+        Some(last) => {
+            require!(last.record(hash_bytes), ErrorCode::RepeatedHash);
+            last.bump = bumps.last_hash.ok_or(ErrorCode::LastHashRequired)?;
+        }
+        None => require!(!reject_repeat_hash, ErrorCode::LastHashRequired),
+    }
+
+    // ── Write Solution PDA ──
+    let solution = &mut accounts.solution;
+    solution.miner = miner_key;
+    solution.recipient = recipient;
+    solution.epoch = epoch_number;
+    solution.nonce = nonce;
+    solution.hash = hash_bytes;
+    solution.bump = bumps.solution;
+    solution.deposit = submit_deposit;
+    solution.difficulty = difficulty;
+    solution.slot = slot;
+    solution.slot_hash = slot_hash;
+    solution.text_hash = text_hash(text);
+    solution.index = index;
+    solution.required_word_count = required_word_count;
+    solution.reward_amount = accounts.mine_state.solution_reward(solution);
+
+    // ── Anti-spam deposit: held in the Solution PDA on top of rent ──
+    // Closing the PDA returns it with the rent: to the miner on claim,
+    // to the closer on close_expired.
+    if submit_deposit > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                Transfer {
+                    from: accounts.miner.to_account_info(),
+                    to: accounts.solution.to_account_info(),
+                },
+            ),
+            submit_deposit,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Error for a failed text check. Non-ASCII gets its own code: it is almost
+/// always a client sending UTF-8 by mistake, worth telling apart in RPC logs.
+fn text_error_code(e: verify::TextError) -> ErrorCode {
+    match e {
+        verify::TextError::NonAscii => ErrorCode::NonAsciiText,
+        _ => ErrorCode::InvalidText,
+    }
+}
+
+/// Counter shard for a miner: first 8 key bytes (big-endian) mod `shards`.
+fn counter_shard(miner: &Pubkey, shards: u8) -> u8 {
+    let k = u64::from_be_bytes(miner.to_bytes()[..8].try_into().unwrap());
+    (k % shards.max(1) as u64) as u8
+}
+
+/// Sum the epoch's `SolutionCounter` shards, passed in shard order, and close
+/// them to `rent_to`. A shard no submit touched was never created and counts 0.
+fn tally_counter_shards<'info>(
+    shards: &'info [AccountInfo<'info>],
+    epoch: u64,
+    shard_count: u8,
+    program_id: &Pubkey,
+    rent_to: &AccountInfo<'info>,
+) -> Result<u64> {
+    require!(shards.len() == shard_count as usize, ErrorCode::CounterShardMismatch);
+    let mut total: u64 = 0;
+    for (i, info) in shards.iter().enumerate() {
+        let (address, _) =
+            Pubkey::find_program_address(&[b"counter", &epoch.to_le_bytes(), &[i as u8]], program_id);
+        require_keys_eq!(info.key(), address, ErrorCode::CounterShardMismatch);
+        if info.owner != program_id {
+            continue;
+        }
+        let counter = Account::<SolutionCounter>::try_from(info)?;
+        total = total.checked_add(counter.count).ok_or(ErrorCode::ArithmeticOverflow)?;
+        counter.close(rent_to.clone())?;
+    }
+    Ok(total)
+}
+
+/// Text dedup key: keccak(text). Seeds the `TextSeen` PDA with the epoch.
+fn text_hash(text: &[u8]) -> [u8; 32] {
+    keccak::hash(text).to_bytes()
+}
+
+/// Commit-reveal commitment: keccak(miner | text | nonce).
+fn solution_commitment(miner: &Pubkey, text: &[u8], nonce: u64) -> [u8; 32] {
+    keccak::hashv(&[miner.as_ref(), text, &nonce.to_le_bytes()]).to_bytes()
+}
+
+/// A commitment may be revealed in its own epoch or the next one.
+fn reveal_in_window(commit_epoch: u64, current_epoch: u64) -> bool {
+    current_epoch == commit_epoch || current_epoch == commit_epoch.saturating_add(1)
+}
+
+/// Drip vesting, then empty `unlocked`: the amount a withdraw mints.
+fn take_unlocked(v: &mut VestingAccount, now: i64) -> Result<u64> {
+    drip_vesting(v, now);
+    let amount = v.unlocked;
+    require!(amount > 0, ErrorCode::NothingToWithdraw);
+    v.unlocked = 0;
+    Ok(amount)
+}
+
+/// Drip vesting: move locked → unlocked based on elapsed time.
+fn drip_vesting(v: &mut VestingAccount, now: i64) {
+    if v.locked == 0 || now <= v.last_update {
+        v.last_update = now;
+        return;
+    }
+    let elapsed = now - v.last_update;
+    let release = if elapsed >= VESTING_DURATION {
+        v.locked
+    } else {
+        // Use u128 to avoid overflow
+        (v.locked as u128 * elapsed as u128 / VESTING_DURATION as u128) as u64
+    };
+    v.unlocked += release;
+    v.locked -= release;
+    v.last_update = now;
+}
+
+/// Keeper's cut of a delegated claim: `reward * bps / 10000`, rounded down.
+fn claim_tip(reward: u64, bps: u16) -> u64 {
+    (reward as u128 * bps as u128 / 10_000) as u64
+}
+
+/// Reward with halving: initial_reward >> (total_mined / HALVING_INTERVAL)
+fn calculate_reward(total_mined: u64, initial_reward: u64) -> u64 {
+    let halvings = total_mined / HALVING_INTERVAL;
+    if halvings >= 64 {
+        return 0;
+    }
+    initial_reward >> halvings
+}
+
+/// Scale the whole-token reward and supply cap to base units: `(initial_reward, max_supply)`.
+///
+/// `None` if `decimals > MAX_DECIMALS` or the scaled cap overflows u64 (100B × 10^9 does).
+fn scaled_supply(decimals: u8) -> Option<(u64, u64)> {
+    if decimals > MAX_DECIMALS {
+        return None;
+    }
+    let unit = 10u64.pow(decimals as u32);
+    Some((INITIAL_REWARD_TOKENS.checked_mul(unit)?, MAX_SUPPLY_TOKENS.checked_mul(unit)?))
+}
+
+/// Reward for the next claim, clipped so `total_supply` never passes `max_supply`.
+///
+/// `excess_bits` (leading zeros beyond the submit difficulty) add 1/8 of the
+/// base reward each, up to `MAX_BONUS_BITS` (2×). `multiplier_bps` (the early
+/// boost times the word bonus; see `boost_bps`, `word_bonus_bps`) applies on top.
+fn claim_reward(
+    total_mined: u64,
+    initial_reward: u64,
+    excess_bits: u64,
+    multiplier_bps: u32,
+    total_supply: u64,
+    max_supply: u64,
+) -> u64 {
+    let base = calculate_reward(total_mined, initial_reward) as u128;
+    let scaled = base * (8 + excess_bits.min(MAX_BONUS_BITS)) as u128 / 8;
+    let boosted = scaled * multiplier_bps as u128 / BPS_ONE as u128;
+    (boosted.min(u64::MAX as u128) as u64).min(max_supply.saturating_sub(total_supply))
+}
+
+/// Reward multiplier for a solution mined under `word_count` required words:
+/// `BPS_ONE` plus `per_word_bps` for each word beyond the fewest a challenge asks for.
+fn word_bonus_bps(word_count: u8, per_word_bps: u16) -> u32 {
+    let base = (words::MAX_REQUIRED - words::WORD_COUNT_THRESHOLDS) as u8;
+    BPS_ONE as u32 + word_count.saturating_sub(base) as u32 * per_word_bps as u32
+}
+
+/// Early-adopter multiplier for a solution from `epoch`: `multiplier_bps` at
+/// epoch 0, decaying linearly to `BPS_ONE` at `boost_epochs`.
+fn boost_bps(epoch: u64, boost_epochs: u64, multiplier_bps: u16) -> u16 {
+    if epoch >= boost_epochs || multiplier_bps <= BPS_ONE {
+        return BPS_ONE;
+    }
+    let extra = (multiplier_bps - BPS_ONE) as u128 * (boost_epochs - epoch) as u128 / boost_epochs as u128;
+    BPS_ONE + extra as u16
+}
+
+/// Solution hash: keccak(seed | slot_hash | [prior_hash] | miner | text | "||" | nonce).
+///
+/// `prior_hash` is only present in chain mode.
+fn solution_hash(
+    challenge_seed: &[u8; 32],
+    slot_hash: &[u8; 32],
+    prior_hash: Option<&[u8; 32]>,
+    miner: &Pubkey,
+    text: &[u8],
+    nonce: u64,
+) -> [u8; 32] {
+    let nonce_bytes = nonce.to_le_bytes();
+    match prior_hash {
+        Some(prior) => keccak::hashv(&[challenge_seed, slot_hash, prior, miner.as_ref(), text, b"||", &nonce_bytes]),
+        None => keccak::hashv(&[challenge_seed, slot_hash, miner.as_ref(), text, b"||", &nonce_bytes]),
+    }
+    .to_bytes()
+}
+
+/// Whether a `["blocked", miner]` address holds a live BlockedMiner account.
+/// The seeds constraint already pins the address, so ownership is enough.
+fn is_blocked(info: &AccountInfo) -> bool {
+    info.owner == &crate::ID && info.lamports() > 0
+}
+
+/// Look up `slot` in raw SlotHashes sysvar data: u64 count, then `(slot, hash)` pairs, newest first.
+fn find_slot_hash(data: &[u8], slot: u64) -> Option<[u8; 32]> {
+    let count = u64::from_le_bytes(data.get(..8)?.try_into().ok()?);
+    for i in 0..count as usize {
+        let entry = data.get(8 + i * 40..8 + (i + 1) * 40)?;
+        let entry_slot = u64::from_le_bytes(entry[..8].try_into().ok()?);
+        if entry_slot == slot {
+            return entry[8..].try_into().ok();
+        }
+        if entry_slot < slot {
+            return None;
+        }
+    }
+    None
+}
+
+/// Most recent entry in raw SlotHashes sysvar data.
+fn latest_slot_hash(data: &[u8]) -> Option<[u8; 32]> {
+    let count = u64::from_le_bytes(data.get(..8)?.try_into().ok()?);
+    if count == 0 {
+        return None;
+    }
+    data.get(16..48)?.try_into().ok()
+}
+
+/// Check that hash has at least `difficulty` leading zero bits.
+fn check_difficulty(hash: &[u8; 32], difficulty: u64) -> bool {
+    let full_bytes = (difficulty / 8) as usize;
+    let remaining_bits = (difficulty % 8) as u8;
+
+    if full_bytes > 32 {
+        return false;
+    }
+    if hash[..full_bytes].iter().any(|&b| b != 0) {
+        return false;
+    }
+
+    if remaining_bits > 0 && full_bytes < 32 {
+        let mask = 0xFF << (8 - remaining_bits);
+        if hash[full_bytes] & mask != 0 {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Submissions are open until `epoch_end_time`; from then on the epoch can be advanced.
+fn epoch_active(now: i64, epoch_end_time: i64) -> bool {
+    now < epoch_end_time
+}
+
+/// Whether `now` falls in the last `cutoff_bps` of the epoch, where submits are
+/// refused. Measured on the current span, so an extension moves the cutoff too.
+fn in_submit_cutoff(now: i64, epoch_start_time: i64, epoch_end_time: i64, cutoff_bps: u16) -> bool {
+    let span = (epoch_end_time as i128 - epoch_start_time as i128).max(0);
+    let cutoff = epoch_end_time as i128 - span * cutoff_bps as i128 / BPS_ONE as i128;
+    cutoff_bps > 0 && now as i128 >= cutoff
+}
+
+/// Whether a solution from `solution_epoch` can be claimed. Advanced epochs always
+/// can; the current epoch only once its end time has passed, and not at all when
+/// `requires_advance` (so a dead crank's unrotated epoch is never paid out early).
+fn epoch_claimable(solution_epoch: u64, current_epoch: u64, now: i64, epoch_end_time: i64, requires_advance: bool) -> bool {
+    match solution_epoch.cmp(&current_epoch) {
+        core::cmp::Ordering::Less => true,
+        core::cmp::Ordering::Equal => !requires_advance && !epoch_active(now, epoch_end_time),
+        core::cmp::Ordering::Greater => false,
+    }
+}
+
+/// The next seed can be committed and previewed from `lead` seconds before the
+/// epoch end until the advance. `lead == 0` disables the preview.
+fn in_preview_window(now: i64, epoch_end_time: i64, lead: i64) -> bool {
+    lead > 0 && now >= epoch_end_time.saturating_sub(lead)
+}
+
+/// Whether a solution from `solution_epoch` is past its claim window at `current_epoch`.
+///
+/// From epoch `solution_epoch + claim_expiry_epochs` on. `claim` and
+/// `close_expired` both test this, so at no epoch are both refused.
+fn claim_expired(current_epoch: u64, solution_epoch: u64, claim_expiry_epochs: u64) -> bool {
+    current_epoch >= solution_epoch.saturating_add(claim_expiry_epochs)
+}
+
+/// Whether `caller` may claim a solution mined by `miner`: anyone, except in
+/// chain mode, where the claim sets the miner's next `prior_hash`.
+fn claim_caller_allowed(chain_mode: bool, caller: &Pubkey, miner: &Pubkey) -> bool {
+    !chain_mode || caller == miner
+}
+
+/// Whether a crank could honestly report `count` solutions: at most `max_reported`
+/// (0 = no ceiling) and at most `MAX_SUBMITS_PER_SECOND` over the epoch's `span` seconds.
+fn plausible_solution_count(count: u64, span: i64, max_reported: u64) -> bool {
+    let physical = MAX_SUBMITS_PER_SECOND.saturating_mul(span.max(0) as u64);
+    count <= physical && (max_reported == 0 || count <= max_reported)
+}
+
+/// Whether an advance at `now` comes too soon after the one at `last_advance_time`.
+fn advance_throttled(now: i64, last_advance_time: i64) -> bool {
+    now < last_advance_time.saturating_add(MIN_ADVANCE_INTERVAL)
+}
+
+/// New end time after an emergency extension, or `None` if `extra_seconds` is not
+/// positive or the epoch would run longer than `MAX_EPOCH_SPAN`.
+fn extended_epoch_end(epoch_start: i64, epoch_end: i64, extra_seconds: i64) -> Option<i64> {
+    if extra_seconds <= 0 {
+        return None;
+    }
+    let new_end = epoch_end.checked_add(extra_seconds)?;
+    if new_end - epoch_start > MAX_EPOCH_SPAN {
+        return None;
+    }
+    Some(new_end)
+}
+
+/// Check `text` against the words `state` requires for `seed`; returns how many there were.
+fn verify_required_words(state: &MineState, seed: &[u8; 32], wordlist: &Wordlist, text: &[u8]) -> Result<u8> {
+    let (count, result) = check_text_rules(state, seed, wordlist, text)?;
+    result.map_err(text_error_code)?;
+    Ok(count)
+}
+
+/// Required-word count for `seed`, and `text` checked against those words and
+/// the state's text rules. Shared by submit and `check_text`.
+///
+/// A derivation short of the difficulty's word count (a list too small to draw
+/// that many distinct words) fails with `WordDerivationFailed` rather than
+/// asking for fewer.
+///
+/// Out of line so `RequiredWords` (~150 bytes) and the slices over it live in
+/// this short-lived frame rather than in `process_submit`'s, which already sits
+/// close to the 4KB SBF stack-frame limit.
+#[inline(never)]
+fn check_text_rules(
+    state: &MineState,
+    seed: &[u8; 32],
+    wordlist: &Wordlist,
+    text: &[u8],
+) -> Result<(u8, core::result::Result<(), verify::TextError>)> {
+    let rw = state.required_words(seed, wordlist);
+    require!(state.derivation_complete(&rw), ErrorCode::WordDerivationFailed);
+    let mut required: [&[u8]; words::MAX_REQUIRED] = [&[]; words::MAX_REQUIRED];
+    for (i, word) in required.iter_mut().enumerate().take(rw.count) {
+        *word = rw.word(i);
+    }
+    let result = verify::verify_text_extended(text, &required[..rw.count], &state.text_rules, &state.ext_rules());
+    Ok((rw.count as u8, result))
+}
+
+/// `TARGET_SOLUTIONS + unique_miners × per_miner_bps / 10000`, within
+/// `TARGET_SOLUTIONS..=max_target`. Off (`per_miner_bps == 0`): `TARGET_SOLUTIONS`.
+fn effective_target(unique_miners: u64, per_miner_bps: u16, max_target: u64) -> u64 {
+    let growth = unique_miners as u128 * per_miner_bps as u128 / BPS_ONE as u128;
+    (TARGET_SOLUTIONS as u128 + growth).min(max_target as u128).max(TARGET_SOLUTIONS as u128) as u64
+}
+
+/// `count × TARGET_SOLUTIONS / target`: the count `step_difficulty` should see so it
+/// steers toward `target` instead of `TARGET_SOLUTIONS`.
+fn scale_to_target(count: u64, target: u64) -> u64 {
+    if target <= TARGET_SOLUTIONS {
+        return count;
+    }
+    (count as u128 * TARGET_SOLUTIONS as u128 / target as u128) as u64
+}
+
+/// CRB minted to an `advance_epoch` caller: `bonus` while permissionless advance
+/// is on, clipped to the remaining supply.
+fn advance_bonus(permissionless: bool, bonus: u64, total_supply: u64, max_supply: u64) -> u64 {
+    if !permissionless {
+        return 0;
+    }
+    bonus.min(max_supply.saturating_sub(total_supply))
+}
+
+/// Bounty the pool can pay without dropping below its rent-exempt reserve.
+fn pool_payout(pool_balance: u64, bounty: u64, rent_min: u64) -> u64 {
+    bounty.min(pool_balance.saturating_sub(rent_min))
+}
+
+/// Lamports above the rent-exempt reserve, which `sweep_lamports` may move.
+fn sweepable_lamports(balance: u64, rent_min: u64) -> u64 {
+    balance.saturating_sub(rent_min)
+}
+
+/// Difficulty floor, ceiling and largest per-epoch step (from MineState).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DifficultyBounds {
+    pub min: u64,
+    pub max: u64,
+    pub max_adj: u64,
+}
+
+impl DifficultyBounds {
+    pub const DEFAULT: DifficultyBounds = DifficultyBounds {
+        min: MIN_DIFFICULTY,
+        max: MAX_DIFFICULTY,
+        max_adj: MAX_DIFFICULTY_ADJ,
+    };
+
+    /// `1 <= min < max <= 256` and a step of at least 1.
+    pub fn is_valid(&self) -> bool {
+        self.min >= 1 && self.min < self.max && self.max <= HASH_BITS && self.max_adj >= 1
+    }
+}
+
+/// Per-epoch difficulty step: log2-dampened move toward `TARGET_SOLUTIONS`, at most ±`max_adj`.
+fn step_difficulty(difficulty: u64, solution_count: u64, bounds: &DifficultyBounds) -> u64 {
+    let target = TARGET_SOLUTIONS;
+    if solution_count > target + target / 5 {
+        let ratio = solution_count / target;
+        let increase = log2_ceil(ratio).clamp(1, bounds.max_adj);
+        difficulty.saturating_add(increase).min(bounds.max)
+    } else if solution_count == 0 {
+        difficulty.saturating_sub(bounds.max_adj).max(bounds.min)
+    } else if solution_count < target.saturating_sub(target / 5) {
+        let ratio = target / solution_count.max(1);
+        let decrease = log2_ceil(ratio).clamp(1, bounds.max_adj);
+        difficulty.saturating_sub(decrease).max(bounds.min)
+    } else {
+        difficulty
+    }
+}
+
+/// Fold this epoch's stepped difficulty (plus any min-hash `bonus`) into the EMA
+/// and derive the next difficulty.
+///
+/// `ema = ema·(n-1)/n + observed/n` in `DIFFICULTY_EMA_ONE` fixed point; the new
+/// difficulty is the rounded EMA, still within ±`max_adj` of `difficulty` and `min..=max`.
+/// Returns `(ema, difficulty)`.
+fn smooth_difficulty(ema: u64, difficulty: u64, solution_count: u64, bonus: u64, bounds: &DifficultyBounds) -> (u64, u64) {
+    let stepped = step_difficulty(difficulty, solution_count, bounds).saturating_add(bonus).min(bounds.max);
+    let observed = stepped * DIFFICULTY_EMA_ONE;
+    let ema = ema - ema / DIFFICULTY_EMA_WINDOW + observed / DIFFICULTY_EMA_WINDOW;
+    let rounded = (ema + DIFFICULTY_EMA_ONE / 2) / DIFFICULTY_EMA_ONE;
+    let next = rounded
+        .clamp(difficulty.saturating_sub(bounds.max_adj), difficulty.saturating_add(bounds.max_adj))
+        .clamp(bounds.min, bounds.max);
+    (ema, next)
+}
+
+/// `count * intended / actual` for an epoch advanced after its end, where
+/// intended is `end - start` (including any extension). On time: `count`.
+fn normalized_solution_count(count: u64, start: i64, end: i64, now: i64) -> u64 {
+    let intended = end.saturating_sub(start);
+    let actual = now.saturating_sub(start);
+    if intended <= 0 || actual <= intended {
+        return count;
+    }
+    (count as u128 * intended as u128 / actual as u128) as u64
+}
+
+/// Leading zero bits of a hash.
+fn leading_zero_bits(hash: &[u8; 32]) -> u64 {
+    let mut bits = 0;
+    for &b in hash {
+        bits += b.leading_zeros() as u64;
+        if b != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+/// Extra difficulty bits implied by the epoch's best hash, at most `weight`.
+///
+/// `solution_count` hashes at `difficulty` bits are expected to produce a best hash
+/// of about `difficulty + log2(solution_count)` bits; only the excess counts.
+/// The crank chooses which solution to present, so this can only be under-reported.
+fn min_hash_bonus(best_hash: &[u8; 32], difficulty: u64, solution_count: u64, weight: u8) -> u64 {
+    let expected = difficulty + log2_ceil(solution_count.max(1));
+    leading_zero_bits(best_hash).saturating_sub(expected).min(weight as u64)
+}
+
+/// Ceiling of log2(n), minimum 1.
+fn log2_ceil(n: u64) -> u64 {
+    if n <= 1 {
+        return 0;
+    }
+    64 - (n - 1).leading_zeros() as u64
+}
+
+// ============================================================
+// Account Structs
+// ============================================================
+
+#[derive(Accounts)]
+#[instruction(decimals: u8)]
+pub struct Initialize<'info> {
+    // init_if_needed so a repeat call reaches the guard instead of "already in use"
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + MineState::INIT_SPACE,
+        seeds = [b"mine_state"],
+        bump,
+        constraint = !mine_state.is_initialized() @ ErrorCode::AlreadyInitialized,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = decimals,
+        mint::authority = mine_state,
+        seeds = [b"mint"],
+        bump,
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(text: String, nonce: u64, recipient: Pubkey, slot: u64, index: u64)]
+pub struct SubmitSolution<'info> {
+    // READ-ONLY: no write lock acquired
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        init,
+        payer = miner,
+        space = 8 + Solution::INIT_SPACE,
+        seeds = [
+            b"solution",
+            miner.key().as_ref(),
+            &mine_state.epoch_number.to_le_bytes(),
+            &index.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub solution: Account<'info, Solution>,
+
+    #[account(
+        seeds = [b"wordlist"],
+        bump = wordlist.bump,
+    )]
+    pub wordlist: Box<Account<'info, Wordlist>>,
+
+    /// Required only in chain mode.
+    #[account(
+        seeds = [b"miner_config", miner.key().as_ref()],
+        bump = miner_config.bump,
+    )]
+    pub miner_config: Option<Account<'info, MinerConfig>>,
+
+    /// Required only with `dedup_texts`; init fails if another submit used this text this epoch.
+    #[account(
+        init,
+        payer = miner,
+        space = 8 + TextSeen::INIT_SPACE,
+        seeds = [b"text", mine_state.epoch_number.to_le_bytes().as_ref(), &text_hash(text.as_bytes())],
+        bump,
+    )]
+    pub text_seen: Option<Account<'info, TextSeen>>,
+
+    /// Required only with counter shards on: this miner's shard of the epoch's solution count.
+    #[account(
+        init_if_needed,
+        payer = miner,
+        space = 8 + SolutionCounter::INIT_SPACE,
+        seeds = [
+            b"counter",
+            mine_state.epoch_number.to_le_bytes().as_ref(),
+            &[counter_shard(&miner.key(), mine_state.counter_shards)],
+        ],
+        bump,
+    )]
+    pub solution_counter: Option<Account<'info, SolutionCounter>>,
+
+    /// Required only with `reject_repeat_hash`: the hash this miner submitted last.
+    #[account(
+        init_if_needed,
+        payer = miner,
+        space = 8 + LastHash::INIT_SPACE,
+        seeds = [b"last_hash", miner.key().as_ref()],
+        bump,
+    )]
+    pub last_hash: Option<Account<'info, LastHash>>,
+
+    /// CHECK: address-constrained to the SlotHashes sysvar; parsed by `find_slot_hash`.
+    #[account(address = sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    /// CHECK: the miner's BlockedMiner PDA address; submits are refused while it exists.
+    #[account(seeds = [b"blocked", miner.key().as_ref()], bump)]
+    pub blocked_miner: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub miner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitSolution<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        init,
+        payer = miner,
+        space = 8 + Commitment::INIT_SPACE,
+        seeds = [b"commitment", miner.key().as_ref(), &mine_state.epoch_number.to_le_bytes()],
+        bump,
+    )]
+    pub commitment: Account<'info, Commitment>,
+
+    #[account(mut)]
+    pub miner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealSolution<'info> {
+    pub submit: SubmitSolution<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"commitment", submit.miner.key().as_ref(), &commitment.epoch.to_le_bytes()],
+        bump = commitment.bump,
+    )]
+    pub commitment: Account<'info, Commitment>,
+}
+
+#[derive(Accounts)]
+pub struct CancelCommitment<'info> {
+    #[account(
+        mut,
+        seeds = [b"commitment", miner.key().as_ref(), &commitment.epoch.to_le_bytes()],
+        bump = commitment.bump,
+        close = miner,
+    )]
+    pub commitment: Account<'info, Commitment>,
+
+    #[account(mut)]
+    pub miner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateMinerConfig<'info> {
+    #[account(
+        init,
+        payer = miner,
+        space = 8 + MinerConfig::INIT_SPACE,
+        seeds = [b"miner_config", miner.key().as_ref()],
+        bump,
+    )]
+    pub miner_config: Account<'info, MinerConfig>,
+
+    #[account(mut)]
+    pub miner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVesting<'info> {
+    #[account(
+        init,
+        payer = miner,
+        space = 8 + VestingAccount::INIT_SPACE,
+        seeds = [b"vesting", miner.key().as_ref()],
+        bump,
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+
+    #[account(mut)]
+    pub miner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"solution",
+            solution.miner.as_ref(),
+            &solution.epoch.to_le_bytes(),
+            &solution.index.to_le_bytes(),
+        ],
+        bump = solution.bump,
+        close = payee,
+    )]
+    pub solution: Account<'info, Solution>,
+
+    /// The payee's VestingAccount: `solution.recipient`'s, or the miner's if it is zeroed.
+    #[account(
+        mut,
+        seeds = [b"vesting", solution.payee().as_ref()],
+        bump = vesting.bump,
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+
+    /// Required only in chain mode; records the claimed hash as the miner's new chain tip.
+    #[account(
+        mut,
+        seeds = [b"miner_config", solution.miner.as_ref()],
+        bump = miner_config.bump,
+    )]
+    pub miner_config: Option<Account<'info, MinerConfig>>,
+
+    /// The solution's TextSeen PDA, if one was created; its rent goes to the payee.
+    #[account(
+        mut,
+        seeds = [b"text", solution.epoch.to_le_bytes().as_ref(), &solution.text_hash],
+        bump = text_seen.bump,
+        close = payee,
+    )]
+    pub text_seen: Option<Account<'info, TextSeen>>,
+
+    /// The solution's payee (see `vesting`); receives the Solution and TextSeen
+    /// rent and the submit deposit. Need not sign.
+    #[account(
+        mut,
+        constraint = payee.key() == solution.payee() @ ErrorCode::InvalidRecipient,
+    )]
+    pub payee: SystemAccount<'info>,
+
+    /// Whoever sends the claim: the miner, the payee or a keeper (the miner
+    /// alone in chain mode).
+    pub caller: Signer<'info>,
+
+    /// Keeper tip destination; ignored when the caller is the miner.
+    #[account(mut, token::mint = mint)]
+    pub caller_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"mint"], bump)]
+    pub mint: Option<Account<'info, Mint>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting", miner.key().as_ref()],
+        bump = vesting.bump,
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"mint"],
+        bump,
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// The miner's associated token account, created on first withdraw.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = miner,
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = miner.key() == vesting.miner @ ErrorCode::Unauthorized
+    )]
+    pub miner: Signer<'info>,
+
+    /// Pays the ATA rent if it has to be created; may be the miner.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawToVault<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting", miner.key().as_ref()],
+        bump = vesting.bump,
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"mint"],
+        bump,
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// Any CRB token account; its owner may be a PDA of another program.
+    #[account(mut, token::mint = mint)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = miner.key() == vesting.miner @ ErrorCode::Unauthorized
+    )]
+    pub miner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct BurnTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        mut,
+        seeds = [b"mint"],
+        bump,
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = holder,
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    pub holder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct AdvanceEpoch<'info> {
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        mut,
+        seeds = [b"incentive_pool"],
+        bump,
+    )]
+    pub incentive_pool: SystemAccount<'info>,
+
+    /// Crank authority, an operator added with `add_crank`, or anyone with
+    /// `permissionless_advance`; receives the advance bounty
+    #[account(
+        mut,
+        constraint = mine_state.permissionless_advance || mine_state.is_crank(&crank.key()) @ ErrorCode::Unauthorized
+    )]
+    pub crank: Signer<'info>,
+
+    /// Optional: the epoch's lowest-hash solution, used when `min_hash_weight > 0`
+    #[account(
+        constraint = best_solution.epoch == mine_state.epoch_number @ ErrorCode::InvalidBestSolution
+    )]
+    pub best_solution: Option<Account<'info, Solution>>,
+
+    /// CHECK: address-constrained to the SlotHashes sysvar; parsed by `latest_slot_hash`.
+    #[account(address = sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    /// Optional: records the ending epoch once `init_epoch_history` has run
+    #[account(
+        mut,
+        seeds = [b"epoch_history"],
+        bump = epoch_history.bump,
+    )]
+    pub epoch_history: Option<Box<Account<'info, EpochHistory>>>,
+
+    /// Advance bonus destination (permissionless advance only)
+    #[account(mut, token::mint = mint)]
+    pub crank_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"mint"], bump)]
+    pub mint: Option<Account<'info, Mint>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    /// Required only with `recent_word_epochs` set, to record the ending epoch's words.
+    #[account(
+        seeds = [b"wordlist"],
+        bump = wordlist.bump,
+    )]
+    pub wordlist: Option<Box<Account<'info, Wordlist>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitEpochHistory<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + EpochHistory::INIT_SPACE,
+        seeds = [b"epoch_history"],
+        bump,
+    )]
+    pub epoch_history: Box<Account<'info, EpochHistory>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CrankAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(constraint = mine_state.is_crank(&crank.key()) @ ErrorCode::Unauthorized)]
+    pub crank: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CommitNextSeed<'info> {
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(constraint = mine_state.is_crank(&crank.key()) @ ErrorCode::Unauthorized)]
+    pub crank: Signer<'info>,
+
+    /// CHECK: address-constrained to the SlotHashes sysvar; parsed by `latest_slot_hash`.
+    #[account(address = sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundIncentivePool<'info> {
+    #[account(
+        mut,
+        seeds = [b"incentive_pool"],
+        bump,
+    )]
+    pub incentive_pool: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReadMineState<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+    )]
+    pub mine_state: Account<'info, MineState>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewDifficulty<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    /// Optional: the epoch's lowest-hash solution, as `advance_epoch` would get it
+    #[account(
+        constraint = best_solution.epoch == mine_state.epoch_number @ ErrorCode::InvalidBestSolution
+    )]
+    pub best_solution: Option<Account<'info, Solution>>,
+}
+
+#[derive(Accounts)]
+pub struct ReadSolution<'info> {
+    #[account(
+        seeds = [
+            b"solution",
+            solution.miner.as_ref(),
+            &solution.epoch.to_le_bytes(),
+            &solution.index.to_le_bytes(),
+        ],
+        bump = solution.bump,
+    )]
+    pub solution: Account<'info, Solution>,
+}
+
+#[derive(Accounts)]
+pub struct ReadEpochHistory<'info> {
+    #[account(
+        seeds = [b"epoch_history"],
+        bump = epoch_history.bump,
+    )]
+    pub epoch_history: Box<Account<'info, EpochHistory>>,
+}
+
+#[derive(Accounts)]
+pub struct ReadDifficultyTrend<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        seeds = [b"epoch_history"],
+        bump = epoch_history.bump,
+    )]
+    pub epoch_history: Box<Account<'info, EpochHistory>>,
+}
+
+#[derive(Accounts)]
+pub struct ReadWords<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        seeds = [b"wordlist"],
+        bump = wordlist.bump,
+    )]
+    pub wordlist: Box<Account<'info, Wordlist>>,
+}
+
+#[derive(Accounts)]
+pub struct SetCrankAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        constraint = authority.key() == mine_state.governance @ ErrorCode::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptCrankAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        constraint = mine_state.pending_crank_authority == Some(new_authority.key()) @ ErrorCode::Unauthorized
+    )]
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetGovernance<'info> {
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        constraint = governance.key() == mine_state.governance @ ErrorCode::Unauthorized
+    )]
+    pub governance: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptGovernance<'info> {
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        constraint = mine_state.pending_governance == Some(new_governance.key()) @ ErrorCode::Unauthorized
+    )]
+    pub new_governance: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(miner: Pubkey)]
+pub struct BlockMiner<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BlockedMiner::INIT_SPACE,
+        seeds = [b"blocked", miner.as_ref()],
+        bump,
+    )]
+    pub blocked_miner: Account<'info, BlockedMiner>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == mine_state.crank_authority @ ErrorCode::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(miner: Pubkey)]
+pub struct UnblockMiner<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        mut,
+        seeds = [b"blocked", miner.as_ref()],
+        bump = blocked_miner.bump,
+        close = authority,
+    )]
+    pub blocked_miner: Account<'info, BlockedMiner>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == mine_state.crank_authority @ ErrorCode::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GuardianAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        constraint = guardian.key() == mine_state.guardian @ ErrorCode::Unauthorized
+    )]
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        constraint = authority.key() == mine_state.governance @ ErrorCode::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SweepLamports<'info> {
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        constraint = authority.key() == mine_state.governance @ ErrorCode::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+
+    /// CHECK: only credited
+    #[account(
+        mut,
+        constraint = treasury.key() != mine_state.key() @ ErrorCode::InvalidTreasury
+    )]
+    pub treasury: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitWordlist<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Wordlist::INIT_SPACE,
+        seeds = [b"wordlist"],
+        bump,
+    )]
+    pub wordlist: Box<Account<'info, Wordlist>>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == mine_state.governance @ ErrorCode::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateWordlist<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        mut,
+        seeds = [b"wordlist"],
+        bump = wordlist.bump,
+    )]
+    pub wordlist: Box<Account<'info, Wordlist>>,
+
+    #[account(
+        constraint = authority.key() == mine_state.governance @ ErrorCode::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResetState<'info> {
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        constraint = authority.key() == mine_state.governance @ ErrorCode::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateMineState<'info> {
+    /// CHECK: may be shorter than the current MineState; resized before decoding
+    #[account(
+        mut,
+        seeds = [b"mine_state"],
+        bump,
+        owner = crate::ID,
+    )]
+    pub mine_state: UncheckedAccount<'info>,
+
+    /// Checked against the decoded state in the handler.
+    #[account(mut)]
+    pub governance: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferSolution<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"solution",
+            solution.miner.as_ref(),
+            &solution.epoch.to_le_bytes(),
+            &solution.index.to_le_bytes(),
+        ],
+        bump = solution.bump,
+        constraint = solution.miner == miner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub solution: Account<'info, Solution>,
+
+    pub miner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExpired<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"solution",
+            solution.miner.as_ref(),
+            &solution.epoch.to_le_bytes(),
+            &solution.index.to_le_bytes(),
+        ],
+        bump = solution.bump,
+        close = closer,
+    )]
+    pub solution: Account<'info, Solution>,
+
+    /// The solution's TextSeen PDA, if one was created; closed with it.
+    #[account(
+        mut,
+        seeds = [b"text", solution.epoch.to_le_bytes().as_ref(), &solution.text_hash],
+        bump = text_seen.bump,
+        close = closer,
+    )]
+    pub text_seen: Option<Account<'info, TextSeen>>,
+
+    /// Anyone can close expired solutions. Rent goes to caller as cleanup incentive.
+    #[account(mut)]
+    pub closer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateMetadata<'info> {
+    #[account(
+        seeds = [b"mine_state"],
+        bump = mine_state.bump,
+        constraint = mine_state.is_current() @ ErrorCode::SchemaOutdated,
+    )]
+    pub mine_state: Account<'info, MineState>,
+
+    #[account(
+        seeds = [b"mint"],
+        bump,
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: Created by Metaplex program, or already created and updated by it
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = payer.key() == mine_state.governance @ ErrorCode::Unauthorized
+    )]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: Metaplex Token Metadata program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+}
+
+// ============================================================
+// State
+// ============================================================
+
+/// Layout versions (`version`, decoded in field order):
+/// - 0: accounts written before `version` existed; fields through
+///   `pending_governance`. `migrate_mine_state` grows them and reads this byte as 0.
+/// - 2: adds `version`. Fields added later go after it and bump `SCHEMA_VERSION`.
+/// - 3: adds `min_difficulty`, `max_difficulty`, `max_difficulty_adj`.
+/// - 4: adds `claim_requires_advance`.
+/// - 5: adds `word_count_thresholds`.
+/// - 6: adds `permissionless_advance`, `advance_bonus`.
+/// - 7: adds `unique_miners`, `target_per_miner_bps`, `max_target_solutions`.
+/// - 8: adds `recent_word_epochs`, `recent_words_age`, `recent_words`.
+/// - 9: adds `empty_epoch_grace`, `consecutive_empty_epochs`.
+/// - 10: adds `boundary_mode`.
+/// - 11: adds `max_digit_pct`.
+/// - 12: adds `min_ending_variety`.
+/// - 13: adds `require_capitals`, `lowercase_start_tolerance`.
+/// - 14: `word_reward_bps`.
+/// - 15: the vowel and space bands (`min_vowel_pct` … `max_space_pct`).
+/// - 16: `submit_cutoff_bps`.
+/// - 17: `reject_repeat_hash`.
+/// - 18: `min_unique_bytes`, `min_sentence_unique_bytes`.
+/// - 19: `bytes_per_extra_match`.
+/// - 20: `max_reported_solutions`.
+/// - 21: `best_hash`, `best_hash_miner`.
+/// - 22: `require_balanced_quotes`.
+///
+/// Each version's layout is a prefix of the next, so `migrate_mine_state` only
+/// zero-fills the tail. The `TextRulesExt` fields are stored one by one in the
+/// version order above; `ext_rules` gathers them.
+#[account]
+#[derive(InitSpace)]
+pub struct MineState {
+    pub total_mined: u64,          // 8   — total solutions ever claimed
+    pub difficulty: u64,           // 8
+    pub challenge_seed: [u8; 32],  // 32
+    pub epoch_number: u64,         // 8
+    pub epoch_start_time: i64,     // 8
+    pub epoch_end_time: i64,       // 8
+    pub solutions_in_epoch: u64,   // 8   — set by crank during advance_epoch
+    pub settled_in_epoch: u64,     // 8   — reserved for compatibility
+    pub total_supply: u64,         // 8   — committed supply (locked + unlocked + released)
+    pub mint: Pubkey,              // 32
+    pub crank_authority: Pubkey,   // 32  — only this address can call advance_epoch
+    pub bump: u8,                  // 1
+    pub chain_mode: bool,          // 1   — submits must commit to the miner's last claimed hash
+    pub text_rules: TextRules,     // 14  — verify_text parameters
+    pub advance_bounty: u64,       // 8   — lamports paid from the incentive pool per advance
+    pub decimals: u8,              // 1   — mint decimals chosen at initialize
+    pub initial_reward: u64,       // 8   — INITIAL_REWARD_TOKENS × 10^decimals
+    pub max_supply: u64,           // 8   — MAX_SUPPLY_TOKENS × 10^decimals
+    pub difficulty_ema: u64,       // 8   — smoothed difficulty, DIFFICULTY_EMA_ONE fixed point
+    pub pending_crank_authority: Option<Pubkey>, // 33 — proposed by set_crank_authority, awaiting accept
+    pub crank_authorities: [Pubkey; MAX_CRANKS], // 160 — extra operators allowed to advance_epoch
+    pub crank_count: u8,           // 1   — active entries in crank_authorities
+    pub guardian: Pubkey,          // 32  — may emergency_halt / resume (independent of the crank)
+    pub halted: bool,              // 1   — emergency halt: submit, claim and advance rejected
+    pub last_advance_time: i64,    // 8   — wall clock of the last advance_epoch (throttle)
+    pub min_hash_weight: u8,       // 1   — max extra difficulty bits from the epoch's best hash (0 = off)
+    pub claim_expiry_epochs: u64,  // 8   — claim window; close_expired allowed after it
+    pub submit_deposit: u64,       // 8   — lamports each submit locks in its Solution PDA
+    pub dedup_texts: bool,         // 1   — submits must create a per-epoch TextSeen PDA
+    pub max_solutions_per_miner: u8, // 1 — solution index range per miner per epoch
+    pub preview_lead_seconds: i64, // 8   — commit_next_seed / preview window before epoch end (0 = off)
+    pub next_challenge_seed: Option<[u8; 32]>, // 33 — committed seed for the next epoch
+    pub counter_shards: u8,        // 1   — SolutionCounter shards this epoch (0 = crank-reported count)
+    pub next_counter_shards: u8,   // 1   — applied at the next advance_epoch
+    pub emission_ended: bool,      // 1   — reward halved to 0: claims mint nothing
+    pub stop_after_emission: bool, // 1   — reject submits once emission_ended
+    pub claim_tip_bps: u16,        // 2   — keeper's share of a claim made for someone else
+    pub normalize_overrun: bool,   // 1   — scale late advances' counts to the intended epoch length
+    pub boost_epochs: u64,         // 8   — early-adopter boost lasts this many epochs
+    pub boost_multiplier_bps: u16, // 2   — boost at epoch 0, decaying to 10000
+    pub governance: Pubkey,        // 32  — sets parameters, wordlist and metadata; appoints the crank
+    pub pending_governance: Option<Pubkey>, // 33 — proposed by set_governance, awaiting accept
+    pub version: u8,               // 1   — layout version (SCHEMA_VERSION when current)
+    pub min_difficulty: u64,       // 8   — difficulty floor
+    pub max_difficulty: u64,       // 8   — difficulty ceiling
+    pub max_difficulty_adj: u64,   // 8   — largest per-epoch difficulty step
+    pub claim_requires_advance: bool, // 1 — claims wait for advance_epoch, not just the end time
+    pub word_count_thresholds: [u64; words::WORD_COUNT_THRESHOLDS], // 40 — difficulty → required word count
+    pub permissionless_advance: bool, // 1 — anyone may advance_epoch (non-cranks need counter shards)
+    pub advance_bonus: u64,        // 8   — CRB base units minted to a permissionless advancer
+    pub unique_miners: u64,        // 8   — distinct miners last epoch, per report_network_growth
+    pub target_per_miner_bps: u16, // 2   — target growth per unique miner (0 = fixed target)
+    pub max_target_solutions: u64, // 8   — ceiling for the scaled target
+    pub recent_word_epochs: u8,    // 1   — epochs a word is kept out of the challenge (0 = off)
+    pub recent_words_age: u8,      // 1   — epochs currently in `recent_words`
+    pub recent_words: [u64; words::WORD_BITMAP_LEN], // 32 — bitmap of recently required wordlist indices
+    pub empty_epoch_grace: u8,     // 1   — empty epochs in a row before the full step down (0 = off)
+    pub consecutive_empty_epochs: u64, // 8 — current run of epochs advanced with no solutions
+    pub boundary_mode: u8,         // 1   — required-word boundaries, one of verify::BOUNDARY_*
+    pub max_digit_pct: u8,         // 1   — most digits, percent of bytes (0 = off)
+    pub min_ending_variety: u8,    // 1   — distinct sentence endings a text must use
+    pub require_capitals: bool,    // 1   — sentences start with a capital
+    pub lowercase_start_tolerance: u8, // 1 — lowercase starts allowed under require_capitals
+    pub word_reward_bps: u16,      // 2   — extra claim reward per required word over the minimum (0 = off)
+    pub min_vowel_pct: u8,         // 1   — vowel band, percent of letters
+    pub max_vowel_pct: u8,         // 1
+    pub min_space_pct: u8,         // 1   — space band, percent of bytes
+    pub max_space_pct: u8,         // 1
+    pub submit_cutoff_bps: u16,    // 2   — final share of each epoch closed to submits (0 = off)
+    pub reject_repeat_hash: bool,  // 1   — submits must pass a LastHash PDA and not repeat its hash
+    pub min_unique_bytes: u8,      // 1   — distinct bytes per text
+    pub min_sentence_unique_bytes: u8, // 1 — distinct bytes per sentence (0 = off)
+    pub bytes_per_extra_match: u16, // 2  — one more match of each required word per this many bytes (0 = off)
+    pub max_reported_solutions: u64, // 8 — ceiling on a crank-reported solution_count (0 = off)
+    pub best_hash: [u8; 32],       // 32  — lowest hash ever claimed (NO_BEST_HASH before the first)
+    pub best_hash_miner: Pubkey,   // 32  — miner of best_hash
+    pub require_balanced_quotes: bool, // 1 — double quotes must come in pairs
+}                                  // total: 813 + 8 discriminator = 821
+
+impl MineState {
+    /// Reward `solution` earns under the current halving, boost and word bonus,
+    /// before the supply clip. Stored on the solution at submit.
+    pub fn solution_reward(&self, solution: &Solution) -> u64 {
+        let excess_bits = leading_zero_bits(&solution.hash).saturating_sub(solution.difficulty);
+        let boost = boost_bps(solution.epoch, self.boost_epochs, self.boost_multiplier_bps);
+        let words = word_bonus_bps(solution.required_word_count, self.word_reward_bps);
+        let multiplier = boost as u32 * words / BPS_ONE as u32;
+        claim_reward(self.total_mined, self.initial_reward, excess_bits, multiplier, 0, self.max_supply)
+    }
+
+    /// Whether `initialize` has run; a freshly created account still has a zero mint.
+    pub fn is_initialized(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+
+    /// Whether the account is on the current layout. Every instruction that
+    /// writes state requires it, so an old layout can only be migrated.
+    pub fn is_current(&self) -> bool {
+        self.version == SCHEMA_VERSION
+    }
+
+    /// `(difficulty_ema, difficulty)` an advance at `now` would set for `solution_count`,
+    /// given the epoch's best hash if the crank supplies one.
+    pub fn adjusted_difficulty(&self, solution_count: u64, now: i64, best_hash: Option<&[u8; 32]>) -> (u64, u64) {
+        // Late advance: scale the count back to the intended epoch length
+        let rate_count = if self.normalize_overrun {
+            normalized_solution_count(solution_count, self.epoch_start_time, self.epoch_end_time, now)
+        } else {
+            solution_count
+        };
+        // Network-scaled target: measure the count against it by rescaling to TARGET_SOLUTIONS
+        let rate_count = scale_to_target(rate_count, self.effective_target());
+        // Best hash of the epoch as a hashrate hint
+        let bonus = best_hash.map_or(0, |hash| min_hash_bonus(hash, self.difficulty, rate_count, self.min_hash_weight));
+        // Empty-epoch grace: a short run of empty epochs only steps down by 1
+        let mut bounds = self.difficulty_bounds();
+        if solution_count == 0 && self.consecutive_empty_epochs.saturating_add(1) < self.empty_epoch_grace as u64 {
+            bounds.max_adj = 1;
+        }
+        smooth_difficulty(self.difficulty_ema, self.difficulty, rate_count, bonus, &bounds)
+    }
+
+    /// Solutions per epoch the difficulty aims for, grown by the reported unique miners.
+    pub fn effective_target(&self) -> u64 {
+        effective_target(self.unique_miners, self.target_per_miner_bps, self.max_target_solutions)
+    }
+
+    /// This state's required words for `seed` at the current difficulty.
+    pub fn required_words(&self, seed: &[u8; 32], wordlist: &Wordlist) -> words::RequiredWords {
+        self.required_words_avoiding(seed, &self.recent_words, wordlist)
+    }
+
+    /// Whether `rw` has the full word count the current difficulty calls for; a
+    /// list too small to draw that many distinct words falls short. Submit and
+    /// the word views refuse a short derivation rather than asking for fewer.
+    pub fn derivation_complete(&self, rw: &words::RequiredWords) -> bool {
+        rw.count == words::word_count_for_difficulty(self.difficulty, &self.word_count_thresholds)
+    }
+
+    /// `required_words` against a given recent-word bitmap (ignored when the feature is off).
+    pub fn required_words_avoiding(
+        &self,
+        seed: &[u8; 32],
+        recent: &[u64; words::WORD_BITMAP_LEN],
+        wordlist: &Wordlist,
+    ) -> words::RequiredWords {
+        let thresholds = &self.word_count_thresholds;
+        if self.recent_word_epochs == 0 {
+            words::derive_words_with_thresholds(seed, self.difficulty, thresholds, &wordlist.source())
+        } else {
+            words::derive_words_avoiding(seed, self.difficulty, thresholds, recent, &wordlist.source())
+        }
+    }
+
+    /// The recent-word bitmap and its age once `ended` (the ending epoch's words)
+    /// is added. A full window starts over with just `ended`, so consecutive
+    /// epochs never share a word.
+    pub fn rolled_recent_words(&self, ended: &words::RequiredWords) -> ([u64; words::WORD_BITMAP_LEN], u8) {
+        let (mut recent, age) = if self.recent_words_age >= self.recent_word_epochs {
+            ([0; words::WORD_BITMAP_LEN], 0)
+        } else {
+            (self.recent_words, self.recent_words_age)
+        };
+        ended.mark(&mut recent);
+        (recent, age + 1)
+    }
+
+    pub fn difficulty_bounds(&self) -> DifficultyBounds {
+        DifficultyBounds {
+            min: self.min_difficulty,
+            max: self.max_difficulty,
+            max_adj: self.max_difficulty_adj,
+        }
+    }
+
+    /// The text rules beyond `text_rules`, gathered from their inline fields.
+    pub fn ext_rules(&self) -> TextRulesExt {
+        TextRulesExt {
+            boundary_mode: self.boundary_mode,
+            max_digit_pct: self.max_digit_pct,
+            min_ending_variety: self.min_ending_variety,
+            require_capitals: self.require_capitals,
+            lowercase_start_tolerance: self.lowercase_start_tolerance,
+            min_vowel_pct: self.min_vowel_pct,
+            max_vowel_pct: self.max_vowel_pct,
+            min_space_pct: self.min_space_pct,
+            max_space_pct: self.max_space_pct,
+            min_unique_bytes: self.min_unique_bytes,
+            min_sentence_unique_bytes: self.min_sentence_unique_bytes,
+            bytes_per_extra_match: self.bytes_per_extra_match,
+            require_balanced_quotes: self.require_balanced_quotes,
+        }
+    }
+
+    /// Store `ext` in the inline fields `ext_rules` reads.
+    pub fn set_ext_rules(&mut self, ext: &TextRulesExt) {
+        self.boundary_mode = ext.boundary_mode;
+        self.max_digit_pct = ext.max_digit_pct;
+        self.min_ending_variety = ext.min_ending_variety;
+        self.require_capitals = ext.require_capitals;
+        self.lowercase_start_tolerance = ext.lowercase_start_tolerance;
+        self.min_vowel_pct = ext.min_vowel_pct;
+        self.max_vowel_pct = ext.max_vowel_pct;
+        self.min_space_pct = ext.min_space_pct;
+        self.max_space_pct = ext.max_space_pct;
+        self.min_unique_bytes = ext.min_unique_bytes;
+        self.min_sentence_unique_bytes = ext.min_sentence_unique_bytes;
+        self.bytes_per_extra_match = ext.bytes_per_extra_match;
+        self.require_balanced_quotes = ext.require_balanced_quotes;
+    }
+
+    /// Default the fields an older layout lacks (zero where zero is invalid)
+    /// and stamp the current `SCHEMA_VERSION`.
+    pub fn migrate(&mut self) {
+        if self.governance == Pubkey::default() {
+            self.governance = self.crank_authority;
+        }
+        if self.max_solutions_per_miner == 0 {
+            self.max_solutions_per_miner = 1;
+        }
+        if self.boost_multiplier_bps == 0 {
+            self.boost_multiplier_bps = BPS_ONE;
+        }
+        if self.claim_expiry_epochs == 0 {
+            self.claim_expiry_epochs = CLAIM_EXPIRY_EPOCHS;
+        }
+        if self.max_target_solutions < TARGET_SOLUTIONS {
+            self.max_target_solutions = TARGET_SOLUTIONS;
+        }
+        if !words::is_valid_word_count_thresholds(&self.word_count_thresholds) {
+            self.word_count_thresholds = words::DEFAULT_WORD_COUNT_THRESHOLDS;
+        }
+        let base = TextRulesExt::BASE;
+        if !self.ext_rules().ratios_valid() {
+            (self.min_vowel_pct, self.max_vowel_pct) = (base.min_vowel_pct, base.max_vowel_pct);
+            (self.min_space_pct, self.max_space_pct) = (base.min_space_pct, base.max_space_pct);
+        }
+        if !self.ext_rules().diversity_valid() {
+            (self.min_unique_bytes, self.min_sentence_unique_bytes) = (base.min_unique_bytes, base.min_sentence_unique_bytes);
+        }
+        if self.best_hash == [0; 32] {
+            self.best_hash = NO_BEST_HASH;
+        }
+        if !self.difficulty_bounds().is_valid() {
+            self.min_difficulty = MIN_DIFFICULTY;
+            self.max_difficulty = MAX_DIFFICULTY;
+            self.max_difficulty_adj = MAX_DIFFICULTY_ADJ;
+        }
+        self.version = SCHEMA_VERSION;
+    }
+
+    /// Restart mining from epoch 0 under `challenge_seed`, as `reset_state` does.
+    /// Configuration, mint, crank_authority, governance and bump stay the same.
+    pub fn reset(&mut self, challenge_seed: [u8; 32], now: i64) {
+        let bounds = self.difficulty_bounds();
+        self.total_mined = 0;
+        self.difficulty = INITIAL_DIFFICULTY.clamp(bounds.min, bounds.max);
+        self.difficulty_ema = self.difficulty * DIFFICULTY_EMA_ONE;
+        self.challenge_seed = challenge_seed;
+        self.epoch_number = 0;
+        self.epoch_start_time = now;
+        self.epoch_end_time = now + EPOCH_DURATION;
+        self.solutions_in_epoch = 0;
+        self.settled_in_epoch = 0;
+        self.total_supply = 0;
+        self.next_challenge_seed = None;
+        // Words from the previous run no longer constrain epoch 0
+        self.recent_words_age = 0;
+        self.recent_words = [0; words::WORD_BITMAP_LEN];
+        self.consecutive_empty_epochs = 0;
+        // The all-time best belongs to the history being discarded
+        self.best_hash = NO_BEST_HASH;
+        self.best_hash_miner = Pubkey::default();
+        // The reward is back at full: submits refused by stop_after_emission resume
+        self.emission_ended = false;
+    }
+
+    /// Count a claim and reserve its reward against the supply cap.
+    pub fn record_claim(&mut self, reward: u64) -> Result<()> {
+        self.total_mined = self.total_mined.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.total_supply = self.total_supply.checked_add(reward).ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// `FEATURE_*` bits for the optional features currently enabled.
+    pub fn feature_flags(&self) -> u32 {
+        [
+            (self.halted, FEATURE_HALTED),
+            (self.chain_mode, FEATURE_CHAIN_MODE),
+            (self.dedup_texts, FEATURE_DEDUP_TEXTS),
+            (self.counter_shards > 0, FEATURE_COUNTER_SHARDS),
+            (self.preview_lead_seconds > 0, FEATURE_CHALLENGE_PREVIEW),
+            (self.normalize_overrun, FEATURE_NORMALIZE_OVERRUN),
+            (self.stop_after_emission, FEATURE_STOP_AFTER_EMISSION),
+            (self.boost_epochs > 0 && self.boost_multiplier_bps > BPS_ONE, FEATURE_EARLY_BOOST),
+            (self.claim_tip_bps > 0, FEATURE_CLAIM_TIP),
+            (self.submit_deposit > 0, FEATURE_SUBMIT_DEPOSIT),
+            (self.min_hash_weight > 0, FEATURE_MIN_HASH),
+            (self.max_solutions_per_miner > 1, FEATURE_MULTI_SOLUTION),
+            (self.claim_requires_advance, FEATURE_CLAIM_REQUIRES_ADVANCE),
+            (self.permissionless_advance, FEATURE_PERMISSIONLESS_ADVANCE),
+            (self.target_per_miner_bps > 0, FEATURE_TARGET_SCALING),
+            (self.recent_word_epochs > 0, FEATURE_RECENT_WORDS),
+            (self.empty_epoch_grace > 0, FEATURE_EMPTY_EPOCH_GRACE),
+            (self.boundary_mode != verify::BOUNDARY_ALPHA, FEATURE_BOUNDARY_MODE),
+            (self.word_reward_bps > 0, FEATURE_WORD_REWARD),
+            (self.submit_cutoff_bps > 0, FEATURE_SUBMIT_CUTOFF),
+            (self.reject_repeat_hash, FEATURE_REPEAT_HASH_CHECK),
+            (self.max_reported_solutions > 0, FEATURE_REPORT_CEILING),
+        ]
+        .iter()
+        .filter(|(on, _)| *on)
+        .fold(0, |flags, (_, bit)| flags | bit)
+    }
+
+    /// `HEALTH_*` bits for the invariants this state breaks; `mint` is the expected mint PDA.
+    pub fn health_failures(&self, mint: &Pubkey) -> u32 {
+        let bounds = self.difficulty_bounds();
+        [
+            (!(bounds.min..=bounds.max).contains(&self.difficulty), HEALTH_DIFFICULTY_RANGE),
+            (!bounds.is_valid(), HEALTH_DIFFICULTY_BOUNDS),
+            (self.epoch_end_time <= self.epoch_start_time, HEALTH_EPOCH_TIMES),
+            (self.total_supply > self.max_supply, HEALTH_SUPPLY),
+            (self.mint != *mint, HEALTH_MINT),
+            (!self.text_rules.is_valid() || !self.ext_rules().is_valid(), HEALTH_TEXT_RULES),
+            (!self.is_current(), HEALTH_SCHEMA),
+            (self.crank_count as usize > MAX_CRANKS, HEALTH_CRANK_SET),
+            (self.governance == Pubkey::default(), HEALTH_GOVERNANCE),
+        ]
+        .iter()
+        .filter(|(failed, _)| *failed)
+        .fold(0, |bits, (_, bit)| bits | bit)
+    }
+
+    /// Release supply-cap headroom for burned tokens.
+    pub fn record_burn(&mut self, amount: u64) -> Result<()> {
+        self.total_supply = self.total_supply.checked_sub(amount).ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Set `emission_ended` once `calculate_reward` hits 0, which it stays at from
+    /// then on (well before 64 halvings for any realistic `initial_reward`).
+    /// True only on the claim that crosses it.
+    pub fn mark_emission_end(&mut self) -> bool {
+        if self.emission_ended || calculate_reward(self.total_mined, self.initial_reward) > 0 {
+            return false;
+        }
+        self.emission_ended = true;
+        true
+    }
+
+    /// Keep `hash` as the all-time best (lowest) claimed hash if it beats the record.
+    /// True only when it does.
+    pub fn record_best_hash(&mut self, hash: &[u8; 32], miner: &Pubkey) -> bool {
+        if *hash >= self.best_hash {
+            return false;
+        }
+        self.best_hash = *hash;
+        self.best_hash_miner = *miner;
+        true
+    }
+
+    /// Whether `key` may call `advance_epoch`: the crank authority or an added operator.
+    pub fn is_crank(&self, key: &Pubkey) -> bool {
+        *key == self.crank_authority || self.cranks().contains(key)
+    }
+
+    pub fn cranks(&self) -> &[Pubkey] {
+        &self.crank_authorities[..self.crank_count as usize]
+    }
+
+    pub fn add_crank(&mut self, crank: Pubkey) -> Result<()> {
+        require!(!self.cranks().contains(&crank), ErrorCode::CrankAlreadyAdded);
+        require!((self.crank_count as usize) < MAX_CRANKS, ErrorCode::CrankSetFull);
+        self.crank_authorities[self.crank_count as usize] = crank;
+        self.crank_count += 1;
+        Ok(())
+    }
+
+    /// Swap-remove `crank`, keeping the active entries contiguous.
+    pub fn remove_crank(&mut self, crank: &Pubkey) -> Result<()> {
+        let idx = self.cranks().iter().position(|k| k == crank).ok_or(ErrorCode::CrankNotFound)?;
+        let last = self.crank_count as usize - 1;
+        self.crank_authorities[idx] = self.crank_authorities[last];
+        self.crank_authorities[last] = Pubkey::default();
+        self.crank_count -= 1;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Solution {
+    pub miner: Pubkey,             // 32  — gas payer (submitter)
+    pub recipient: Pubkey,         // 32  — reward and rent receiver (zeroed = the miner)
+    pub epoch: u64,                // 8
+    pub nonce: u64,                // 8
+    pub hash: [u8; 32],            // 32
+    pub bump: u8,                  // 1
+    pub deposit: u64,              // 8   — anti-spam lamports held above rent
+    pub difficulty: u64,           // 8   — difficulty the hash was checked against at submit
+    pub slot: u64,                 // 8   — SlotHashes entry the hash is bound to
+    pub slot_hash: [u8; 32],       // 32
+    pub text_hash: [u8; 32],       // 32  — keccak(text); locates the TextSeen PDA
+    pub index: u64,                // 8   — per-miner slot within the epoch (PDA seed)
+    pub required_word_count: u8,   // 1   — required words the text had to contain
+    pub reward_amount: u64,        // 8   — reward fixed at submit; claim pays it, clipped to the supply cap
+}                                  // total: 218 + 8 discriminator = 226
+
+impl Solution {
+    pub fn meets_difficulty(&self) -> bool {
+        check_difficulty(&self.hash, self.difficulty)
+    }
+
+    /// Whose VestingAccount the reward goes to: `recipient`, or the miner if it is zeroed.
+    pub fn payee(&self) -> Pubkey {
+        if self.recipient == Pubkey::default() {
+            self.miner
+        } else {
+            self.recipient
+        }
+    }
+
+    /// What claiming pays: `reward_amount`, clipped so `total_supply` never passes `max_supply`.
+    pub fn payout(&self, total_supply: u64, max_supply: u64) -> u64 {
+        self.reward_amount.min(max_supply.saturating_sub(total_supply))
+    }
+
+    /// Set a new recipient; returns the previous payee.
+    pub fn transfer(&mut self, recipient: Pubkey) -> Pubkey {
+        let from = self.payee();
+        self.recipient = recipient;
+        from
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Wordlist {
+    pub count: u16,                // 2   — number of words
+    #[max_len(WORDLIST_MAX_BYTES)]
+    pub data: Vec<u8>,             // 4 + 2304 — packed `[len, bytes...]` entries
+    pub category_ends: [u16; 4],   // 8   — exclusive end index per category (zeros = uncategorized)
+    pub bump: u8,                  // 1
+}                                  // total: 2319 + 8 discriminator = 2327
+
+impl Wordlist {
+    /// Validate and append one word (4–8 lowercase ASCII letters).
+    pub fn push(&mut self, word: &[u8]) -> Result<()> {
+        require!(words::is_valid_word(word), ErrorCode::InvalidWord);
+        require!(
+            (self.count as usize) < words::MAX_WORDLIST_SIZE
+                && self.data.len() + 1 + word.len() <= WORDLIST_MAX_BYTES,
+            ErrorCode::WordlistFull
+        );
+        self.data.push(word.len() as u8);
+        self.data.extend_from_slice(word);
+        self.count += 1;
+        Ok(())
+    }
+
+    pub fn source(&self) -> words::PackedWords<'_> {
+        words::PackedWords::new(&self.data, self.count as usize, self.category_ends)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Commitment {
+    pub miner: Pubkey,             // 32
+    pub commitment: [u8; 32],      // 32  — keccak(miner | text | nonce)
+    pub epoch: u64,                // 8   — epoch of the commit; reveal by epoch + 1
+    pub bump: u8,                  // 1
+}                                  // total: 73 + 8 discriminator = 81
+
+/// One shard of an epoch's solution count (`seeds = ["counter", epoch, shard]`).
+#[account]
+#[derive(InitSpace)]
+pub struct SolutionCounter {
+    pub count: u64,                // 8
+    pub bump: u8,                  // 1
+}                                  // total: 9 + 8 discriminator = 17
+
+/// Marks a text as submitted in an epoch (`seeds = ["text", epoch, keccak(text)]`).
+#[account]
+#[derive(InitSpace)]
+pub struct TextSeen {
+    pub bump: u8,                  // 1
+}                                  // total: 1 + 8 discriminator = 9
+
+/// The hash a miner submitted last (`seeds = ["last_hash", miner]`), kept across
+/// epochs so a text/nonce can't be paid again if a challenge seed ever repeats.
+#[account]
+#[derive(InitSpace)]
+pub struct LastHash {
+    pub hash: [u8; 32],            // 32
+    pub bump: u8,                  // 1
+}                                  // total: 33 + 8 discriminator = 41
+
+impl LastHash {
+    /// Store `hash` as the latest; `false` (and unchanged) if it repeats the stored one.
+    pub fn record(&mut self, hash: [u8; 32]) -> bool {
+        if self.hash == hash {
+            return false;
+        }
+        self.hash = hash;
+        true
+    }
+}
+
+/// One ended epoch, as written by `advance_epoch`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct EpochRecord {
+    pub epoch: u64,                // 8
+    pub difficulty: u64,           // 8   — difficulty submits were held to
+    pub solution_count: u64,       // 8   — count reported at the advance
+    pub start_time: i64,           // 8
+}                                  // total: 32
+
+/// Last `EPOCH_HISTORY_LEN` epochs (`seeds = ["epoch_history"]`), kept out of
+/// MineState so submits don't read it. Written only by `advance_epoch`.
+#[account]
+#[derive(InitSpace)]
+pub struct EpochHistory {
+    pub head: u8,                  // 1   — slot the next record goes to (oldest once full)
+    pub len: u8,                   // 1   — filled slots, up to EPOCH_HISTORY_LEN
+    pub records: [EpochRecord; EPOCH_HISTORY_LEN], // 1024
+    pub bump: u8,                  // 1
+}                                  // total: 1027 + 8 discriminator = 1035
+
+impl EpochHistory {
+    /// Write `record` over the oldest slot.
+    pub fn push(&mut self, record: EpochRecord) {
+        self.records[self.head as usize] = record;
+        self.head = ((self.head as usize + 1) % EPOCH_HISTORY_LEN) as u8;
+        self.len = (self.len as usize + 1).min(EPOCH_HISTORY_LEN) as u8;
+    }
+
+    /// Stored records, oldest first.
+    pub fn ordered(&self) -> impl Iterator<Item = &EpochRecord> {
+        let start = (self.head as usize + EPOCH_HISTORY_LEN - self.len as usize) % EPOCH_HISTORY_LEN;
+        (0..self.len as usize).map(move |i| &self.records[(start + i) % EPOCH_HISTORY_LEN])
+    }
+
+    /// Stored records for epochs `start..start + count`, oldest first.
+    pub fn range(&self, start: u64, count: u64) -> impl Iterator<Item = &EpochRecord> {
+        let end = start.saturating_add(count);
+        self.ordered().filter(move |r| (start..end).contains(&r.epoch))
+    }
+
+    /// `(sum, count)` of the difficulties of the newest `window` records.
+    pub fn recent_difficulty(&self, window: usize) -> (u64, u64) {
+        let skip = (self.len as usize).saturating_sub(window);
+        self.ordered()
+            .skip(skip)
+            .fold((0, 0), |(sum, n), r| (sum + r.difficulty, n + 1))
+    }
+}
+
+/// Direction of the difficulty against its recent average.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trend {
+    Rising,
+    Falling,
+    Stable,
+}
+
+impl Trend {
+    /// `Stable` within half a bit of the average (or with no history), else the
+    /// side `difficulty` is on.
+    pub fn of(difficulty: u64, sum: u64, count: u64) -> Self {
+        let (scaled, twice) = (2 * difficulty * count, 2 * sum);
+        if count == 0 || scaled.abs_diff(twice) <= count {
+            Trend::Stable
+        } else if scaled > twice {
+            Trend::Rising
+        } else {
+            Trend::Falling
+        }
+    }
+}
+
+/// A miner barred from submitting (`seeds = ["blocked", miner]`); exists only while blocked.
+#[account]
+#[derive(InitSpace)]
+pub struct BlockedMiner {
+    pub miner: Pubkey,             // 32
+    pub bump: u8,                  // 1
+}                                  // total: 33 + 8 discriminator = 41
+
+#[account]
+#[derive(InitSpace)]
+pub struct MinerConfig {
+    pub miner: Pubkey,             // 32  — owner
+    pub prior_hash: [u8; 32],      // 32  — hash of the last claimed solution (chain tip)
+    pub bump: u8,                  // 1
+}                                  // total: 65 + 8 discriminator = 73
+
+#[account]
+#[derive(InitSpace)]
+pub struct VestingAccount {
+    pub miner: Pubkey,             // 32  — gas payer / owner
+    pub locked: u64,               // 8   — vesting, not yet available
+    pub unlocked: u64,             // 8   — vested, ready to withdraw
+    pub last_update: i64,          // 8   — last drip calculation time
+    pub bump: u8,                  // 1
+}                                  // total: 57 + 8 discriminator = 65
+
+// ============================================================
+// Events
+// ============================================================
+
+#[event]
+pub struct PoolFunded {
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub balance: u64,              // pool balance after funding
+}
+
+#[event]
+pub struct LamportsSwept {
+    pub treasury: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,            // mine_state balance after the sweep (its rent-exempt reserve)
+}
+
+/// Stable polling view of `MineState`; `version` changes when fields do.
+#[event]
+pub struct MineSummary {
+    pub version: u8,
+    pub epoch_number: u64,
+    pub difficulty: u64,
+    pub challenge_seed: [u8; 32],
+    pub total_supply: u64,
+    pub remaining_supply: u64,
+    pub epoch_end_time: i64,
+    pub chain_mode: bool,
+    pub halted: bool,              // emergency halt by the guardian
+    pub current_reward: u64,       // reward the next claim would add
+}
+
+impl MineSummary {
+    pub fn new(state: &MineState) -> Self {
+        Self {
+            version: SUMMARY_VERSION,
+            epoch_number: state.epoch_number,
+            difficulty: state.difficulty,
+            challenge_seed: state.challenge_seed,
+            total_supply: state.total_supply,
+            remaining_supply: state.max_supply.saturating_sub(state.total_supply),
+            epoch_end_time: state.epoch_end_time,
+            chain_mode: state.chain_mode,
+            halted: state.halted,
+            current_reward: calculate_reward(state.total_mined, state.initial_reward)
+                .min(state.max_supply.saturating_sub(state.total_supply)),
+        }
+    }
+}
+
+/// Lamport cost of one submit → claim cycle.
+#[event]
+pub struct CostEstimate {
+    pub solution_rent: u64,        // Solution PDA rent, paid on submit, refunded on claim
+    pub submit_deposit: u64,       // anti-spam deposit, paid on submit, refunded on claim
+    pub submit_fee: u64,           // transaction fee for submit_solution
+    pub claim_fee: u64,            // transaction fee for claim
+    pub upfront: u64,              // needed in the wallet to submit
+    pub net_cost: u64,             // spent per cycle once the rent is refunded
+}
+
+impl CostEstimate {
+    pub fn new(rent: &Rent, submit_deposit: u64) -> Self {
+        let solution_rent = rent.minimum_balance(8 + Solution::INIT_SPACE);
+        Self {
+            solution_rent,
+            submit_deposit,
+            submit_fee: LAMPORTS_PER_SIGNATURE,
+            claim_fee: LAMPORTS_PER_SIGNATURE,
+            upfront: solution_rent + submit_deposit + LAMPORTS_PER_SIGNATURE,
+            net_cost: 2 * LAMPORTS_PER_SIGNATURE,
+        }
+    }
+}
+
+/// A crank's unique-miner report and the target it now implies.
+#[event]
+pub struct NetworkGrowth {
+    pub epoch: u64,
+    pub unique_miners: u64,
+    pub target_solutions: u64,
+}
+
+/// One page of `dump_epoch_history`: the stored records in the requested
+/// range. Epochs missing from `records` have left the ring (or never ran).
+#[event]
+pub struct EpochHistoryPage {
+    pub start: u64,
+    pub count: u64,
+    pub oldest_epoch: Option<u64>, // oldest epoch still in the ring
+    pub records: Vec<EpochRecord>,
+}
+
+impl EpochHistoryPage {
+    pub fn new(history: &EpochHistory, start: u64, count: u64) -> Self {
+        Self {
+            start,
+            count,
+            oldest_epoch: history.ordered().next().map(|r| r.epoch),
+            records: history.range(start, count).copied().collect(),
+        }
+    }
+}
+
+/// What `advance_epoch` would do to the difficulty for a given count.
+#[event]
+pub struct DifficultyPreview {
+    pub epoch: u64,
+    pub solution_count: u64,
+    pub current_difficulty: u64,
+    pub difficulty: u64,
+    pub difficulty_ema: u64,
+}
+
+/// Current difficulty against the recent average (`difficulty_trend`).
+#[event]
+pub struct DifficultyTrend {
+    pub epoch: u64,
+    pub difficulty: u64,
+    pub average_x100: u64,         // mean of the last `epochs` records, ×100
+    pub epochs: u64,               // records averaged, up to TREND_WINDOW
+    pub trend: Trend,
+}
+
+impl DifficultyTrend {
+    pub fn new(state: &MineState, history: &EpochHistory) -> Self {
+        let (sum, epochs) = history.recent_difficulty(TREND_WINDOW);
+        Self {
+            epoch: state.epoch_number,
+            difficulty: state.difficulty,
+            average_x100: (sum * 100).checked_div(epochs).unwrap_or(0),
+            epochs,
+            trend: Trend::of(state.difficulty, sum, epochs),
+        }
+    }
+}
+
+/// Whether the current epoch takes submits, by on-chain time.
+#[event]
+pub struct EpochStatus {
+    pub active: bool,              // a submit now would pass the epoch, cutoff, halt and emission checks
+    pub seconds_remaining: i64,    // until epoch_end_time; 0 once it has passed
+    pub epoch_number: u64,
+    pub difficulty: u64,
+    pub required_word_count: u8,
+}
+
+impl EpochStatus {
+    pub fn new(state: &MineState, now: i64) -> Self {
+        let refused = state.halted
+            || (state.emission_ended && state.stop_after_emission)
+            || in_submit_cutoff(now, state.epoch_start_time, state.epoch_end_time, state.submit_cutoff_bps);
+        Self {
+            active: epoch_active(now, state.epoch_end_time) && !refused,
+            seconds_remaining: state.epoch_end_time.saturating_sub(now).max(0),
+            epoch_number: state.epoch_number,
+            difficulty: state.difficulty,
+            required_word_count: words::word_count_for_difficulty(state.difficulty, &state.word_count_thresholds) as u8,
+        }
+    }
+}
+
+/// Countdown to the next reward halving.
+#[event]
+pub struct HalvingCountdown {
+    pub halvings: u64,                      // halvings so far
+    pub solutions_until_halving: u64,       // claims left before the reward halves
+    pub epochs_until_halving: Option<u64>,  // at last epoch's solution count; None if it was 0
+}
+
+impl HalvingCountdown {
+    pub fn new(state: &MineState) -> Self {
+        let solutions_until_halving = HALVING_INTERVAL - state.total_mined % HALVING_INTERVAL;
+        let per_epoch = state.solutions_in_epoch;
+        Self {
+            halvings: state.total_mined / HALVING_INTERVAL,
+            solutions_until_halving,
+            epochs_until_halving: (per_epoch > 0).then(|| solutions_until_halving.div_ceil(per_epoch)),
+        }
+    }
+}
+
+/// Whose reward a Solution PDA pays. The submit hash commits to `miner`,
+/// so only that miner could have produced it.
+#[event]
+pub struct SolutionMiner {
+    pub solution: Pubkey,
+    pub miner: Pubkey,
+    pub recipient: Pubkey,
+    pub epoch: u64,
+}
+
+impl SolutionMiner {
+    pub fn new(key: Pubkey, solution: &Solution) -> Self {
+        Self {
+            solution: key,
+            miner: solution.miner,
+            recipient: solution.payee(),
+            epoch: solution.epoch,
+        }
+    }
+}
+
+/// Vested CRB minted into a miner-designated vault by `withdraw_to_vault`.
+#[event]
+pub struct VaultWithdrawal {
+    pub miner: Pubkey,
+    pub vault: Pubkey,
+    pub vault_owner: Pubkey,
+    pub amount: u64,
+}
+
+/// An unclaimed solution's payout moved from one payee to another.
+#[event]
+pub struct SolutionTransferred {
+    pub solution: Pubkey,
+    pub miner: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub epoch: u64,
+}
+
+/// `check_text` result for the current epoch; `error` is `None` if the text passes.
+#[event]
+pub struct TextCheck {
+    pub epoch: u64,
+    pub difficulty: u64,
+    pub required_words: u8,
+    pub error: Option<verify::TextError>,
+}
+
+/// Required words for an epoch, in the order they must appear.
+#[event]
+pub struct WordOrder {
+    pub epoch: u64,
+    pub words: Vec<String>,
+    pub seed_offsets: Vec<u8>,     // challenge_seed byte each word was drawn from (a u16 at offset, offset + 1)
+}
+
+impl WordOrder {
+    pub fn new(epoch: u64, rw: &words::RequiredWords) -> Self {
+        let words = (0..rw.count)
+            .map(|i| String::from_utf8_lossy(&rw.words[i][..rw.lens[i]]).into_owned())
+            .collect();
+        let seed_offsets = (0..rw.count as u8).map(|i| i * 2).collect();
+        Self { epoch, words, seed_offsets }
+    }
+}
+
+#[event]
+pub struct HealthStatus {
+    pub healthy: bool,
+    pub failed_checks: u32,        // HEALTH_* bits
+}
+
+#[event]
+pub struct FeatureFlags {
+    pub schema_version: u8,
+    pub flags: u32,                // FEATURE_* bits
+}
+
+#[event]
+pub struct MineStateMigrated {
+    pub from: u8,
+    pub to: u8,
+}
+
+#[event]
+pub struct TokensBurned {
+    pub burner: Pubkey,
+    pub amount: u64,
+}
+
+/// A claim set a new all-time lowest solution hash.
+#[event]
+pub struct NewBestHash {
+    pub miner: Pubkey,
+    pub hash: [u8; 32],
+    pub leading_zero_bits: u64,
+    pub epoch: u64,                // the solution's epoch
+}
+
+/// The reward has halved to zero; claims no longer mint.
+#[event]
+pub struct EmissionEnded {
+    pub epoch: u64,
+    pub total_mined: u64,
+}
+
+#[event]
+pub struct EpochExtended {
+    pub epoch: u64,
+    pub epoch_end_time: i64,       // new end time
+}
+
+// ============================================================
+// Errors
+// ============================================================
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Text verification failed")]
+    InvalidText,
+    #[msg("Hash does not meet difficulty requirement")]
+    InsufficientDifficulty,
+    #[msg("Maximum token supply reached")]
+    MaxSupplyReached,
+    #[msg("Current epoch has ended, call advance_epoch first")]
+    EpochEnded,
+    #[msg("Epoch has not ended yet")]
+    EpochNotEnded,
+    #[msg("Recipient does not match")]
+    InvalidRecipient,
+    #[msg("Solution claim period has expired")]
+    ClaimExpired,
+    #[msg("Solution has not expired yet")]
+    NotExpired,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Nothing to withdraw")]
+    NothingToWithdraw,
+    #[msg("Chain mode requires the miner's MinerConfig account")]
+    MinerConfigRequired,
+    #[msg("Wordlist entries must be 4-8 lowercase ASCII letters")]
+    InvalidWord,
+    #[msg("Wordlist is full")]
+    WordlistFull,
+    #[msg("Text rules out of bounds")]
+    InvalidTextRules,
+    #[msg("Category ranges must be non-decreasing and within the wordlist")]
+    InvalidCategories,
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Unknown verification preset")]
+    InvalidPreset,
+    #[msg("Decimals too large for the supply cap")]
+    InvalidDecimals,
+    #[msg("Extension must be positive and keep the epoch within 24 hours")]
+    InvalidExtension,
+    #[msg("No crank authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Crank operator set is full")]
+    CrankSetFull,
+    #[msg("Crank operator already authorized")]
+    CrankAlreadyAdded,
+    #[msg("Crank operator not found")]
+    CrankNotFound,
+    #[msg("Mining is halted by the guardian")]
+    Halted,
+    #[msg("advance_epoch called too soon after the previous advance")]
+    AdvanceThrottled,
+    #[msg("Min-hash weight out of bounds")]
+    InvalidMinHashWeight,
+    #[msg("Best solution is not from the current epoch")]
+    InvalidBestSolution,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[msg("Claim expiry must be at least 10 epochs")]
+    InvalidClaimExpiry,
+    #[msg("Stored solution hash does not meet its recorded difficulty")]
+    SolutionHashInvalid,
+    #[msg("Revealed solution does not match the commitment")]
+    CommitmentMismatch,
+    #[msg("Commitment must be revealed in its epoch or the next")]
+    RevealWindowClosed,
+    #[msg("Slot is not in the SlotHashes sysvar")]
+    SlotHashNotFound,
+    #[msg("Text dedup is on: the TextSeen account is required")]
+    TextSeenRequired,
+    #[msg("Solution index is not below max_solutions_per_miner")]
+    SolutionIndexOutOfRange,
+    #[msg("Solutions per miner must be between 1 and 16")]
+    InvalidSolutionCap,
+    #[msg("Text contains non-ASCII bytes")]
+    NonAsciiText,
+    #[msg("Next challenge preview is not open")]
+    PreviewWindowClosed,
+    #[msg("Next challenge seed already committed this epoch")]
+    NextSeedCommitted,
+    #[msg("Preview lead must be between 0 and the epoch duration")]
+    InvalidPreviewLead,
+    #[msg("Counter shards on: the SolutionCounter account is required")]
+    CounterRequired,
+    #[msg("SolutionCounter shards missing, out of order or wrong epoch")]
+    CounterShardMismatch,
+    #[msg("Reported solution count does not match the counter shards")]
+    SolutionCountMismatch,
+    #[msg("Counter shards must be at most 16")]
+    InvalidCounterShards,
+    #[msg("Emission has ended: submissions are closed")]
+    EmissionEnded,
+    #[msg("Claim tip must be at most 1000 bps")]
+    InvalidClaimTip,
+    #[msg("Boost multiplier must be between 10000 and 30000 bps")]
+    InvalidBoost,
+    #[msg("MineState is already at the current version")]
+    AlreadyMigrated,
+    #[msg("This miner is blocked from submitting")]
+    MinerBlocked,
+    #[msg("Difficulty bounds need 1 <= min < max <= 256 and a step of at least 1")]
+    InvalidDifficultyBounds,
+    #[msg("Word count thresholds must be strictly increasing")]
+    InvalidWordCountThresholds,
+    #[msg("Permissionless advances need counter shards so the solution count is checked on-chain")]
+    UnverifiedSolutionCount,
+    #[msg("Advance bonus may be at most a tenth of the initial reward")]
+    InvalidAdvanceBonus,
+    #[msg("Max target must be between TARGET_SOLUTIONS and 1000")]
+    InvalidTargetScaling,
+    #[msg("Recent-word window may be at most 8 epochs")]
+    InvalidRecentWordEpochs,
+    #[msg("Wordlist account required while recent words are tracked")]
+    WordlistRequired,
+    #[msg("History page must hold 1 to 16 epochs")]
+    InvalidHistoryPage,
+    #[msg("Boundary mode must be 0 (letters), 1 (letters and digits) or 2 (whitespace)")]
+    InvalidBoundaryMode,
+    #[msg("Word reward may be at most 5000 bps per word")]
+    InvalidWordReward,
+    #[msg("Mine state is already initialized")]
+    AlreadyInitialized,
+    #[msg("Submit cutoff may be at most 5000 bps of the epoch")]
+    InvalidSubmitCutoff,
+    #[msg("Submissions are closed for the rest of this epoch")]
+    SubmitCutoff,
+    #[msg("Treasury must be an account other than the mine state")]
+    InvalidTreasury,
+    #[msg("Repeat-hash check is on: the LastHash account is required")]
+    LastHashRequired,
+    #[msg("Solution hash repeats this miner's previous submit")]
+    RepeatedHash,
+    #[msg("MineState is on an old schema version: run migrate_mine_state first")]
+    SchemaOutdated,
+    #[msg("Reported solution count is above max_reported_solutions or what the epoch could hold")]
+    ImplausibleSolutionCount,
+    #[msg("Reported-solution ceiling must be 0 or at least 1000")]
+    InvalidReportCeiling,
+    #[msg("Wordlist yielded fewer distinct required words than the difficulty calls for")]
+    WordDerivationFailed,
+    #[msg("In chain mode only the miner may claim its solutions")]
+    DelegatedClaimInChainMode,
+}
+
+// ============================================================
+// Tests
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meets(hash: &[u8; 32], difficulty: u64) -> bool {
+        check_difficulty(hash, difficulty)
+    }
+
+    const SLOT_HASH: [u8; 32] = [5u8; 32];
+
+    /// Grind a nonce whose hash meets `difficulty` for the given chain tip.
+    fn mine(seed: &[u8; 32], prior: Option<&[u8; 32]>, miner: &Pubkey, text: &[u8], difficulty: u64) -> u64 {
+        (0u64..).find(|&n| meets(&solution_hash(seed, &SLOT_HASH, prior, miner, text, n), difficulty)).unwrap()
+    }
+
+    #[test]
+    fn test_sweep_lamports_keeps_reserve() {
+        let rent_min = Rent::default().minimum_balance(8 + MineState::INIT_SPACE);
+        // Nothing stray: nothing to sweep
+        assert_eq!(sweepable_lamports(rent_min, rent_min), 0);
+        // Stray lamports go, the reserve stays
+        assert_eq!(sweepable_lamports(rent_min + 12_345, rent_min), 12_345);
+        assert_eq!(rent_min + 12_345 - sweepable_lamports(rent_min + 12_345, rent_min), rent_min);
+        // An underfunded account is never debited
+        assert_eq!(sweepable_lamports(rent_min - 1, rent_min), 0);
+    }
+
+    #[test]
+    fn test_incentive_pool_payout() {
+        let rent_min = Rent::default().minimum_balance(0);
+        let bounty = 100_000;
+        // Unfunded pool pays nothing
+        assert_eq!(pool_payout(0, bounty, rent_min), 0);
+        // Funding raises the balance; the next advance draws the full bounty
+        let balance = rent_min + 250_000;
+        assert_eq!(pool_payout(balance, bounty, rent_min), bounty);
+        // Near-empty pool pays what it can while keeping its rent reserve
+        assert_eq!(pool_payout(rent_min + 40_000, bounty, rent_min), 40_000);
+        assert_eq!(pool_payout(rent_min, bounty, rent_min), 0);
+    }
+
+    #[test]
+    fn test_scaled_supply_six_decimals() {
+        let (initial_reward, max_supply) = scaled_supply(6).unwrap();
+        assert_eq!(initial_reward, 25_000 * 1_000_000);
+        assert_eq!(max_supply, 100_000_000_000 * 1_000_000);
+        assert_eq!(calculate_reward(0, initial_reward), initial_reward);
+        assert_eq!(calculate_reward(HALVING_INTERVAL, initial_reward), initial_reward / 2);
+        // Emission over every halving era never exceeds the scaled cap
+        let emitted: u128 = (0..64)
+            .map(|era| calculate_reward(era * HALVING_INTERVAL, initial_reward) as u128 * HALVING_INTERVAL as u128)
+            .sum();
+        assert!(emitted <= max_supply as u128);
+        // The original 3-decimal deployment is unchanged
+        assert_eq!(scaled_supply(3), Some((25_000_000, 100_000_000_000_000)));
+        // 100B × 10^9 does not fit in u64
+        assert_eq!(scaled_supply(MAX_DECIMALS), None);
+        assert_eq!(scaled_supply(MAX_DECIMALS + 1), None);
+    }
+
+    fn sample_state() -> MineState {
+        let (initial_reward, max_supply) = scaled_supply(3).unwrap();
+        let ext = TextRulesExt::DEFAULT;
+        MineState {
+            total_mined: HALVING_INTERVAL + 7,
+            difficulty: 17,
+            challenge_seed: [3u8; 32],
+            epoch_number: 42,
+            epoch_start_time: 1_000_000,
+            epoch_end_time: 1_000_000 + EPOCH_DURATION,
+            solutions_in_epoch: 12,
+            settled_in_epoch: 0,
+            total_supply: 60_000_000_000_000,
+            mint: Pubkey::new_unique(),
+            crank_authority: Pubkey::new_unique(),
+            bump: 255,
+            chain_mode: true,
+            text_rules: TextRules::DEFAULT,
+            advance_bounty: 0,
+            decimals: 3,
+            initial_reward,
+            max_supply,
+            difficulty_ema: 17 * DIFFICULTY_EMA_ONE,
+            pending_crank_authority: None,
+            crank_authorities: [Pubkey::default(); MAX_CRANKS],
+            crank_count: 0,
+            guardian: Pubkey::new_unique(),
+            halted: false,
+            last_advance_time: 1_000_000,
+            min_hash_weight: 0,
+            claim_expiry_epochs: CLAIM_EXPIRY_EPOCHS,
+            submit_deposit: 0,
+            dedup_texts: false,
+            max_solutions_per_miner: 1,
+            preview_lead_seconds: 0,
+            next_challenge_seed: None,
+            counter_shards: 0,
+            next_counter_shards: 0,
+            emission_ended: false,
+            stop_after_emission: false,
+            claim_tip_bps: 0,
+            normalize_overrun: false,
+            boost_epochs: 0,
+            boost_multiplier_bps: BPS_ONE,
+            governance: Pubkey::new_unique(),
+            pending_governance: None,
+            version: SCHEMA_VERSION,
+            min_difficulty: MIN_DIFFICULTY,
+            max_difficulty: MAX_DIFFICULTY,
+            max_difficulty_adj: MAX_DIFFICULTY_ADJ,
+            claim_requires_advance: false,
+            word_count_thresholds: words::DEFAULT_WORD_COUNT_THRESHOLDS,
+            permissionless_advance: false,
+            advance_bonus: 0,
+            unique_miners: 0,
+            target_per_miner_bps: 0,
+            max_target_solutions: TARGET_SOLUTIONS,
+            recent_word_epochs: 0,
+            recent_words_age: 0,
+            recent_words: [0; words::WORD_BITMAP_LEN],
+            empty_epoch_grace: 0,
+            consecutive_empty_epochs: 0,
+            boundary_mode: ext.boundary_mode,
+            max_digit_pct: ext.max_digit_pct,
+            min_ending_variety: ext.min_ending_variety,
+            require_capitals: ext.require_capitals,
+            lowercase_start_tolerance: ext.lowercase_start_tolerance,
+            word_reward_bps: 0,
+            min_vowel_pct: ext.min_vowel_pct,
+            max_vowel_pct: ext.max_vowel_pct,
+            min_space_pct: ext.min_space_pct,
+            max_space_pct: ext.max_space_pct,
+            submit_cutoff_bps: 0,
+            reject_repeat_hash: false,
+            min_unique_bytes: ext.min_unique_bytes,
+            min_sentence_unique_bytes: ext.min_sentence_unique_bytes,
+            bytes_per_extra_match: ext.bytes_per_extra_match,
+            max_reported_solutions: 0,
+            best_hash: NO_BEST_HASH,
+            best_hash_miner: Pubkey::default(),
+            require_balanced_quotes: ext.require_balanced_quotes,
+        }
+    }
+
+    #[test]
+    fn test_mine_summary_matches_state() {
+        let state = sample_state();
+        let summary = MineSummary::new(&state);
+        assert_eq!(summary.version, SUMMARY_VERSION);
+        assert_eq!(summary.epoch_number, state.epoch_number);
+        assert_eq!(summary.difficulty, state.difficulty);
+        assert_eq!(summary.challenge_seed, state.challenge_seed);
+        assert_eq!(summary.total_supply, state.total_supply);
+        assert_eq!(summary.remaining_supply, 40_000_000_000_000);
+        assert_eq!(summary.epoch_end_time, state.epoch_end_time);
+        assert!(summary.chain_mode);
+        assert!(!summary.halted);
+        // One halving in
+        assert_eq!(summary.current_reward, state.initial_reward / 2);
+
+        // Near the cap the reward is clipped to what's left
+        let mut state = state;
+        state.total_supply = state.max_supply - 5;
+        let summary = MineSummary::new(&state);
+        assert_eq!(summary.remaining_supply, 5);
+        assert_eq!(summary.current_reward, 5);
+    }
+
+    #[test]
+    fn test_early_boost() {
+        // 2× over the first 100 epochs, decaying linearly
+        assert_eq!(boost_bps(0, 100, 20_000), 20_000);
+        assert_eq!(boost_bps(50, 100, 20_000), 15_000);
+        assert_eq!(boost_bps(99, 100, 20_000), 10_100);
+        assert_eq!(boost_bps(100, 100, 20_000), BPS_ONE);
+        // Off by default
+        assert_eq!(boost_bps(0, 0, BPS_ONE), BPS_ONE);
+        assert_eq!(boost_bps(0, 100, BPS_ONE), BPS_ONE);
+
+        let state = sample_state();
+        let base = calculate_reward(0, state.initial_reward);
+        let boosted = claim_reward(0, state.initial_reward, 0, boost_bps(50, 100, 20_000).into(), 0, state.max_supply);
+        assert_eq!(boosted, base * 3 / 2);
+        // Halving applies underneath the boost
+        let halved = claim_reward(HALVING_INTERVAL, state.initial_reward, 0, 20_000, 0, state.max_supply);
+        assert_eq!(halved, base);
+        // And the supply cap on top of it
+        let capped = claim_reward(0, state.initial_reward, 0, 20_000, state.max_supply - base, state.max_supply);
+        assert_eq!(capped, base);
+    }
+
+    #[test]
+    fn test_word_reward() {
+        let state = sample_state();
+        let base = calculate_reward(0, state.initial_reward);
+        let reward = |words: u8, per_word_bps: u16, boost: u16| {
+            let multiplier = boost as u32 * word_bonus_bps(words, per_word_bps) / BPS_ONE as u32;
+            claim_reward(0, state.initial_reward, 0, multiplier, 0, state.max_supply)
+        };
+
+        // Off: every word count earns the same
+        assert_eq!(reward(3, 0, BPS_ONE), base);
+        assert_eq!(reward(8, 0, BPS_ONE), base);
+
+        // +25% per word over three: a six-word (difficulty 30) solution out-earns a three-word one
+        assert_eq!(reward(3, 2_500, BPS_ONE), base);
+        assert_eq!(reward(6, 2_500, BPS_ONE), base * 7 / 4);
+        assert!(reward(6, 2_500, BPS_ONE) > reward(3, 2_500, BPS_ONE));
+        assert_eq!(words::word_count_for_difficulty(30, &words::DEFAULT_WORD_COUNT_THRESHOLDS), 6);
+        assert_eq!(words::word_count_for_difficulty(8, &words::DEFAULT_WORD_COUNT_THRESHOLDS), 3);
+
+        // Stacks with the early boost; counts below three never earn less than the base
+        assert_eq!(reward(5, 5_000, 15_000), base * 3);
+        assert_eq!(reward(0, 5_000, BPS_ONE), base);
+        // The combined multiplier can't overflow u32 at both caps
+        assert!(MAX_BOOST_BPS as u64 * word_bonus_bps(8, MAX_WORD_REWARD_BPS) as u64 <= u32::MAX as u64);
+    }
+
+    #[test]
+    fn test_late_advance_normalized() {
+        let start = 1_000;
+        let end = start + EPOCH_DURATION;
+        // On time, or early within the throttle: unchanged
+        assert_eq!(normalized_solution_count(80, start, end, end), 80);
+        assert_eq!(normalized_solution_count(80, start, end, end - 10), 80);
+        // Advanced one full epoch late: 70 raw over 2× the time reads as 35
+        let late = normalized_solution_count(70, start, end, end + EPOCH_DURATION);
+        assert_eq!(late, 35);
+        // A high raw count that would raise difficulty reads under target
+        assert!(step_difficulty(20, 70, &DifficultyBounds::DEFAULT) > 20);
+        assert!(step_difficulty(20, late, &DifficultyBounds::DEFAULT) < 20);
+        // Extended epochs use their extended length
+        assert_eq!(normalized_solution_count(80, start, end + 300, end + 300), 80);
+        // Degenerate timestamps don't divide by zero
+        assert_eq!(normalized_solution_count(80, start, start, start + 10), 80);
+    }
+
+    #[test]
+    fn test_difficulty_bonus_reward() {
+        let state = sample_state();
+        let base = calculate_reward(0, state.initial_reward);
+        let reward = |hash: &[u8; 32], difficulty: u64| {
+            let excess = leading_zero_bits(hash).saturating_sub(difficulty);
+            claim_reward(0, state.initial_reward, excess, BPS_ONE.into(), 0, state.max_supply)
+        };
+        // Exactly at threshold: 12 zero bits for difficulty 12
+        let mut hash = [0xffu8; 32];
+        hash[0] = 0;
+        hash[1] = 0x0f;
+        assert_eq!(leading_zero_bits(&hash), 12);
+        assert_eq!(reward(&hash, 12), base);
+        // 3 bits above: +3/8
+        hash[1] = 0x01;
+        assert_eq!(reward(&hash, 12), base + base * 3 / 8);
+        // Far above: capped at 2×
+        assert_eq!(reward(&[0u8; 32], 12), 2 * base);
+        // The bonus is still clipped by the supply cap
+        let r = claim_reward(0, state.initial_reward, MAX_BONUS_BITS, BPS_ONE.into(), state.max_supply - base, state.max_supply);
+        assert_eq!(r, base);
+    }
+
+    #[test]
+    fn test_reward_fixed_at_submit() {
+        let mut state = sample_state();
+        state.total_mined = 0;
+        state.total_supply = 0;
+        state.boost_epochs = 10;
+        state.boost_multiplier_bps = 20_000;
+        state.word_reward_bps = 1_000;
+        let base = calculate_reward(0, state.initial_reward);
+
+        let seed = [4u8; 32];
+        let miner = Pubkey::new_unique();
+        let text = b"audited text";
+        let nonce = mine(&seed, None, &miner, text, 10);
+        let hash = solution_hash(&seed, &SLOT_HASH, None, &miner, text, nonce);
+        let mut solution = Solution {
+            miner,
+            recipient: Pubkey::default(),
+            epoch: 0,
+            nonce,
+            hash,
+            bump: 255,
+            deposit: 0,
+            difficulty: 10,
+            slot: 100,
+            slot_hash: SLOT_HASH,
+            text_hash: text_hash(text),
+            index: 0,
+            required_word_count: 5,
+            reward_amount: 0,
+        };
+
+        // Submit: excess bits, the epoch-0 boost and two extra words, all fixed now
+        solution.reward_amount = state.solution_reward(&solution);
+        let excess = leading_zero_bits(&hash) - 10;
+        let expected = claim_reward(0, state.initial_reward, excess, 20_000 * 12_000 / BPS_ONE as u32, 0, state.max_supply);
+        assert_eq!(solution.reward_amount, expected);
+        assert!(solution.reward_amount >= base * 2 * 12 / 10);
+
+        // Claim after a halving and a boost change pays what was recorded
+        state.total_mined = HALVING_INTERVAL;
+        state.boost_multiplier_bps = BPS_ONE;
+        let paid = solution.payout(state.total_supply, state.max_supply);
+        assert_eq!(paid, solution.reward_amount);
+        state.record_claim(paid).unwrap();
+        assert_eq!(state.total_supply, solution.reward_amount);
+        // ...unless the supply cap is closer
+        assert_eq!(solution.payout(state.max_supply - 7, state.max_supply), 7);
+    }
+
+    #[test]
+    fn test_claim_at_supply_cap() {
+        let mut state = sample_state();
+        let (reward, _) = scaled_supply(3).unwrap();
+        // One claim short of the cap: the last claim is clipped to fill it exactly
+        state.total_mined = 0;
+        state.total_supply = state.max_supply - reward / 2;
+        let r = claim_reward(state.total_mined, state.initial_reward, 0, BPS_ONE.into(), state.total_supply, state.max_supply);
+        assert_eq!(r, reward / 2);
+        state.record_claim(r).unwrap();
+        assert_eq!(state.total_supply, state.max_supply);
+        // At the cap the next claim reserves nothing and does not wrap
+        let r = claim_reward(state.total_mined, state.initial_reward, 0, BPS_ONE.into(), state.total_supply, state.max_supply);
+        assert_eq!(r, 0);
+        state.record_claim(r).unwrap();
+        assert_eq!(state.total_supply, state.max_supply);
+        assert_eq!(state.total_mined, 2);
+
+        // Counter overflow is an error, not a wrap or panic
+        state.total_mined = u64::MAX;
+        assert!(state.record_claim(0).is_err());
+        state.total_mined = 0;
+        state.total_supply = u64::MAX;
+        assert!(state.record_claim(1).is_err());
+    }
+
+    #[test]
+    fn test_halving_shift_edge() {
+        let reward = 25_000_000;
+        assert_eq!(calculate_reward(63 * HALVING_INTERVAL, reward), reward >> 63);
+        assert_eq!(calculate_reward(63 * HALVING_INTERVAL, u64::MAX), 1);
+        assert_eq!(calculate_reward(64 * HALVING_INTERVAL - 1, u64::MAX), 1);
+        assert_eq!(calculate_reward(64 * HALVING_INTERVAL, u64::MAX), 0);
+        assert_eq!(calculate_reward(u64::MAX, u64::MAX), 0);
+    }
+
+    #[test]
+    fn test_claim_expiry_window() {
+        assert!(!claim_expired(599, 100, CLAIM_EXPIRY_EPOCHS));
+        assert!(claim_expired(600, 100, CLAIM_EXPIRY_EPOCHS));
+        // Shortening the window expires existing solutions retroactively
+        assert!(claim_expired(120, 100, 20));
+        assert!(!claim_expired(119, 100, 20));
+        assert!(!claim_expired(u64::MAX - 1, u64::MAX - 5, CLAIM_EXPIRY_EPOCHS));
+    }
+
+    #[test]
+    fn test_expiry_boundary_claim_or_close() {
+        // claim needs an ended epoch and !claim_expired; close_expired needs claim_expired.
+        // Once the solution's epoch has ended, exactly one of them applies.
+        let (solution_epoch, now, end) = (100, 1_000, 500);
+        for expiry in [MIN_CLAIM_EXPIRY_EPOCHS, 20, CLAIM_EXPIRY_EPOCHS] {
+            for requires_advance in [false, true] {
+                for current in solution_epoch + 1..=solution_epoch + expiry + 2 {
+                    let claimable = epoch_claimable(solution_epoch, current, now, end, requires_advance)
+                        && !claim_expired(current, solution_epoch, expiry);
+                    let closeable = claim_expired(current, solution_epoch, expiry);
+                    assert!(claimable != closeable, "expiry {expiry}, epoch {current}");
+                }
+                // The boundary itself belongs to close_expired
+                assert!(!claim_expired(solution_epoch + expiry - 1, solution_epoch, expiry));
+                assert!(claim_expired(solution_epoch + expiry, solution_epoch, expiry));
+            }
+            // Still in its own (unended) epoch: neither yet, and never closeable
+            assert!(!epoch_claimable(solution_epoch, solution_epoch, 0, end, false));
+            assert!(!claim_expired(solution_epoch, solution_epoch, expiry));
+        }
+    }
+
+    #[test]
+    fn test_cost_estimate_matches_rent() {
+        let rent = Rent::default();
+        let est = CostEstimate::new(&rent, 0);
+        assert_eq!(8 + Solution::INIT_SPACE, 226);
+        assert_eq!(est.solution_rent, rent.minimum_balance(226));
+        assert_eq!(est.upfront, est.solution_rent + est.submit_fee);
+        assert_eq!(est.net_cost, est.submit_fee + est.claim_fee);
+        // A deposit raises the upfront amount but, being refunded, not the net cost
+        let with_deposit = CostEstimate::new(&rent, 1_000_000);
+        assert_eq!(with_deposit.upfront, est.upfront + 1_000_000);
+        assert_eq!(with_deposit.net_cost, est.net_cost);
+    }
+
+    #[test]
+    fn test_halving_countdown() {
+        let mut state = sample_state();
+        state.total_mined = 3 * HALVING_INTERVAL + 500_000;
+        state.solutions_in_epoch = 0;
+        let c = HalvingCountdown::new(&state);
+        assert_eq!(c.halvings, 3);
+        assert_eq!(c.solutions_until_halving, 1_500_000);
+        assert_eq!(c.epochs_until_halving, None);
+        // 1.5M claims at 1000 per epoch; a partial epoch rounds up
+        state.solutions_in_epoch = 1000;
+        assert_eq!(HalvingCountdown::new(&state).epochs_until_halving, Some(1500));
+        state.solutions_in_epoch = 1001;
+        assert_eq!(HalvingCountdown::new(&state).epochs_until_halving, Some(1499));
+        // Exactly on a boundary: a full interval to go
+        state.total_mined = HALVING_INTERVAL;
+        let c = HalvingCountdown::new(&state);
+        assert_eq!((c.halvings, c.solutions_until_halving), (1, HALVING_INTERVAL));
+    }
+
+    #[test]
+    fn test_epoch_status() {
+        let mut state = sample_state();
+        let end = state.epoch_end_time;
+        let status = EpochStatus::new(&state, end - 90);
+        assert!(status.active);
+        assert_eq!(status.seconds_remaining, 90);
+        assert_eq!((status.epoch_number, status.difficulty), (state.epoch_number, state.difficulty));
+        assert_eq!(
+            status.required_word_count as usize,
+            words::word_count_for_difficulty(state.difficulty, &state.word_count_thresholds)
+        );
+
+        // Closed from the end time on, with nothing left to count down
+        let status = EpochStatus::new(&state, end);
+        assert!(!status.active);
+        assert_eq!(status.seconds_remaining, 0);
+        assert_eq!(EpochStatus::new(&state, end + 1_000).seconds_remaining, 0);
+
+        // Time left but submits refused: the cutoff or a halt
+        state.submit_cutoff_bps = 1_000; // last 60s of the 600s epoch
+        assert!(!EpochStatus::new(&state, end - 30).active);
+        assert!(EpochStatus::new(&state, end - 90).active);
+        state.halted = true;
+        let status = EpochStatus::new(&state, end - 90);
+        assert!(!status.active);
+        assert_eq!(status.seconds_remaining, 90);
+    }
+
+    #[test]
+    fn test_word_order_matches_derivation() {
+        let seed = [42u8; 32];
+        for difficulty in [8, 12, 18, 45] {
+            let rw = words::derive_words(&seed, difficulty, &words::DefaultWords);
+            let order = WordOrder::new(9, &rw);
+            assert_eq!(order.epoch, 9);
+            assert_eq!(order.words.len(), rw.count);
+            for i in 0..rw.count {
+                assert_eq!(order.words[i].as_bytes(), &rw.words[i][..rw.lens[i]]);
+                assert_eq!(order.seed_offsets[i] as usize, i * 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_ascii_error_code() {
+        let mut text = String::from("The café on the corner was rather interesting and pleasant for an early spring day. ");
+        while text.len() < 300 {
+            text.push_str("Have you ever wondered about the patterns that emerge in the garden? ");
+        }
+        let e = verify::verify_text_detailed(text.as_bytes(), &[], &TextRules::DEFAULT).unwrap_err();
+        assert_eq!(e, verify::TextError::NonAscii);
+        assert_eq!(text_error_code(e) as u32, ErrorCode::NonAsciiText as u32);
+        assert_eq!(text_error_code(verify::TextError::NoQuestion) as u32, ErrorCode::InvalidText as u32);
+    }
+
+    #[test]
+    fn test_metadata_exists() {
+        // Fresh: system-owned and empty, so create
+        assert!(!metadata_exists(&system_program::ID, true));
+        // Created: Metaplex-owned with data, so update
+        assert!(metadata_exists(&mpl_token_metadata::ID, false));
+        // Anything else is left for Metaplex's create to reject
+        assert!(!metadata_exists(&mpl_token_metadata::ID, true));
+        assert!(!metadata_exists(&Pubkey::new_unique(), false));
+    }
+
+    #[test]
+    fn test_submit_cutoff() {
+        let (start, end) = (10_000, 10_000 + EPOCH_DURATION);
+        // Off: the whole epoch is open
+        assert!(!in_submit_cutoff(end - 1, start, end, 0));
+
+        // Last 10% closed: with 600 s epochs, from 60 s before the end
+        let bps = 1_000;
+        let cutoff = end - EPOCH_DURATION / 10;
+        assert!(!in_submit_cutoff(start, start, end, bps));
+        assert!(!in_submit_cutoff(cutoff - 1, start, end, bps));
+        assert!(in_submit_cutoff(cutoff, start, end, bps));
+        assert!(in_submit_cutoff(end - 1, start, end, bps));
+
+        // An extension reopens submits and moves the cutoff to the new end
+        assert!(!in_submit_cutoff(cutoff, start, end + 300, bps));
+        assert!(in_submit_cutoff(end + 300 - 1, start, end + 300, bps));
+    }
+
+    #[test]
+    fn test_preview_window() {
+        let end = 10_000;
+        // Disabled by default
+        assert!(!in_preview_window(end - 1, end, 0));
+        assert!(!in_preview_window(end + 100, end, 0));
+        // 120 s lead: withheld before, available from end - 120 until the advance
+        assert!(!in_preview_window(end - 121, end, 120));
+        assert!(in_preview_window(end - 120, end, 120));
+        assert!(in_preview_window(end - 1, end, 120));
+        assert!(in_preview_window(end + 300, end, 120));
+        // An extended epoch pushes the window out with it
+        assert!(!in_preview_window(end - 60, end + 300, 120));
+    }
+
+    #[test]
+    fn test_counter_shard() {
+        let miners: Vec<Pubkey> = (0..64).map(|_| Pubkey::new_unique()).collect();
+        for shards in [1u8, 4, MAX_COUNTER_SHARDS] {
+            for m in &miners {
+                let shard = counter_shard(m, shards);
+                assert!(shard < shards);
+                assert_eq!(shard, counter_shard(m, shards), "stable per miner");
+            }
+        }
+        // Disabled (0) maps everyone to shard 0 rather than dividing by zero
+        assert!(miners.iter().all(|m| counter_shard(m, 0) == 0));
+        // Miners spread over more than one shard
+        let mut hit = [false; 4];
+        miners.iter().for_each(|m| hit[counter_shard(m, 4) as usize] = true);
+        assert!(hit.iter().filter(|&&h| h).count() > 1);
+    }
+
+    #[test]
+    fn test_wordlist_rejects_overlong_word() {
+        let mut list = Wordlist { count: 0, data: Vec::new(), category_ends: [0; 4], bump: 255 };
+        list.push(b"consider").unwrap();
+        // 9 bytes: would be truncated to "discover" by derive_words, so it's refused
+        assert!(list.push(b"discovery").is_err());
+        assert_eq!(list.count, 1);
+        assert_eq!(list.data, b"\x08consider");
+    }
+
+    #[test]
+    fn test_verify_required_words_at_max_difficulty() {
+        // The out-of-line frame stays small even with all 8 words live
+        assert!(core::mem::size_of::<words::RequiredWords>() + core::mem::size_of::<[&[u8]; words::MAX_REQUIRED]>() <= 512);
+
+        let mut list = Wordlist { count: 0, data: Vec::new(), category_ends: words::DEFAULT_CATEGORY_ENDS, bump: 255 };
+        words::WORDLIST.iter().for_each(|w| list.push(w.as_bytes()).unwrap());
+        let mut state = sample_state();
+        state.difficulty = 45;
+        let seed = [0x5au8; 32];
+        let rw = state.required_words(&seed, &list);
+        assert_eq!(rw.count, words::MAX_REQUIRED);
+        let w: Vec<&str> = (0..rw.count).map(|i| core::str::from_utf8(rw.word(i)).unwrap()).collect();
+        let text = format!(
+            "The weather in the morning was rather interesting and everyone spoke about {} \
+             while the old clock in the quiet hallway slowly ticked toward {}. \
+             Have you ever wondered whether careful thinking can explain {} \
+             as the evening light fades across the distant hills and {}? \
+             The ancient trees in the garden were standing tall near {} \
+             and their branches reached toward the bright sky above {}. \
+             The morning air felt crisp and full of motion. \
+             Is there anything more beautiful than a quiet evening with {} \
+             when the gentle rain keeps falling softly on the roof and {}?",
+            w[0], w[1], w[2], w[3], w[4], w[5], w[6], w[7]
+        );
+        assert!(verify_required_words(&state, &seed, &list, text.as_bytes()).is_ok());
+        // Drop the last word: rejected
+        let short = text.replacen(w[7], "nothing", 1);
+        assert!(verify_required_words(&state, &seed, &list, short.as_bytes()).is_err());
+
+        // check_text reports the same verdict, naming the failed rule
+        assert_eq!(check_text_rules(&state, &seed, &list, text.as_bytes()).unwrap(), (8, Ok(())));
+        assert_eq!(
+            check_text_rules(&state, &seed, &list, short.as_bytes()).unwrap(),
+            (8, Err(verify::TextError::MissingRequiredWords))
+        );
+    }
+
+    #[test]
+    fn test_word_derivation_shortfall_rejected() {
+        // Two words can't fill the three distinct slots difficulty 8 calls for
+        let mut list = Wordlist { count: 0, data: Vec::new(), category_ends: [0; 4], bump: 255 };
+        list.push(b"weather").unwrap();
+        list.push(b"ancient").unwrap();
+        let mut state = sample_state();
+        state.difficulty = 8;
+        let seed = [0x21u8; 32];
+        assert_eq!(words::word_count_for_difficulty(state.difficulty, &state.word_count_thresholds), 3);
+        let rw = state.required_words(&seed, &list);
+        assert_eq!(rw.count, 2);
+        // word_order and next_challenge_preview refuse to show it too
+        assert!(!state.derivation_complete(&rw));
+
+        // Rejected outright, even for a text that holds both derived words
+        let text = "The weather in the morning was rather interesting and pleasant for an early \
+            spring day in the northern hemisphere. Have you ever wondered whether the ancient \
+            workings of the world can truly be understood through simple observation and careful \
+            thinking about the patterns that emerge in everything around us? The morning air felt \
+            crisp and full of motion. Yes, the old trees of nature in the garden were standing tall \
+            and their branches reached toward the bright sky.";
+        assert_eq!(
+            verify_required_words(&state, &seed, &list, text.as_bytes()).unwrap_err(),
+            ErrorCode::WordDerivationFailed.into()
+        );
+        // check_text refuses it the same way instead of reporting the text as passing
+        assert_eq!(
+            check_text_rules(&state, &seed, &list, text.as_bytes()).unwrap_err(),
+            ErrorCode::WordDerivationFailed.into()
+        );
+
+        // A third word fills the slots, and the same text (now holding all three) passes
+        list.push(b"nature").unwrap();
+        assert!(state.derivation_complete(&state.required_words(&seed, &list)));
+        assert_eq!(verify_required_words(&state, &seed, &list, text.as_bytes()).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_recent_words_not_repeated() {
+        let mut list = Wordlist { count: 0, data: Vec::new(), category_ends: words::DEFAULT_CATEGORY_ENDS, bump: 255 };
+        words::WORDLIST.iter().for_each(|w| list.push(w.as_bytes()).unwrap());
+        let mut state = sample_state();
+        state.difficulty = 45; // 8 words
+        state.recent_word_epochs = 2;
+        let indices = |rw: &words::RequiredWords| rw.indices[..rw.count].to_vec();
+
+        // Advance through a run of epochs as advance_epoch does
+        let mut prior: Vec<Vec<u16>> = Vec::new();
+        for epoch in 0..12u8 {
+            state.challenge_seed = keccak::hash(&[epoch]).to_bytes();
+            let rw = state.required_words(&state.challenge_seed, &list);
+            let current = indices(&rw);
+            assert_eq!(current.len(), 8);
+            // Disjoint from the previous epoch, and from the one before that
+            // whenever the window still holds it
+            if let Some(last) = prior.last() {
+                assert!(current.iter().all(|i| !last.contains(i)));
+            }
+            if state.recent_words_age == 2 {
+                assert!(current.iter().all(|i| !prior[prior.len() - 2].contains(i)));
+            }
+            (state.recent_words, state.recent_words_age) = state.rolled_recent_words(&rw);
+            assert!((1..=2).contains(&state.recent_words_age));
+            prior.push(current);
+        }
+
+        // Off: the same seeds draw the plain derivation, recent bitmap or not
+        state.recent_word_epochs = 0;
+        let plain = words::derive_words(&state.challenge_seed, 45, &words::DefaultWords);
+        assert_eq!(indices(&state.required_words(&state.challenge_seed, &list)), indices(&plain));
+        assert_eq!(state.feature_flags() & FEATURE_RECENT_WORDS, 0);
+    }
+
+    #[test]
+    fn test_double_initialize_rejected() {
+        // What init_if_needed hands the guard on the first call: discriminator, then zeros
+        let mut data = vec![0u8; 8 + MineState::INIT_SPACE];
+        data[..8].copy_from_slice(&<MineState as anchor_lang::Discriminator>::DISCRIMINATOR);
+        let fresh = MineState::try_deserialize(&mut &data[..]).unwrap();
+        assert!(!fresh.is_initialized());
+
+        // Any later call finds the mint recorded and the guard fails with AlreadyInitialized
+        let state = sample_state();
+        assert!(state.is_initialized());
+
+        // A pre-migration account is shorter than the current layout: it fails to
+        // deserialize before the guard runs, with AccountDidNotDeserialize
+        let mut old = Vec::new();
+        state.try_serialize(&mut old).unwrap();
+        old.truncate(old.len() - 1);
+        assert_eq!(
+            MineState::try_deserialize(&mut &old[..]).err(),
+            Some(anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+        );
+    }
+
+    #[test]
+    fn test_reset_state() {
+        let mut state = sample_state();
+        state.stop_after_emission = true;
+        state.emission_ended = true;
+        state.recent_word_epochs = 2;
+        state.recent_words_age = 2;
+        state.recent_words[0] = 0b1011;
+        state.empty_epoch_grace = 3;
+        state.consecutive_empty_epochs = 5;
+        state.best_hash = [1u8; 32];
+        state.best_hash_miner = Pubkey::new_unique();
+        state.reset([4u8; 32], 1_000);
+        assert_eq!((state.total_mined, state.total_supply, state.epoch_number), (0, 0, 0));
+        assert_eq!(state.challenge_seed, [4u8; 32]);
+        assert_eq!(state.epoch_end_time, 1_000 + EPOCH_DURATION);
+        // Emission starts over, so stop_after_emission no longer refuses submits
+        assert!(!state.emission_ended);
+        assert!(state.stop_after_emission);
+        assert_eq!(state.difficulty, INITIAL_DIFFICULTY);
+        assert_eq!(state.recent_words_age, 0);
+        assert_eq!(state.recent_words, [0; words::WORD_BITMAP_LEN]);
+        assert_eq!(state.recent_word_epochs, 2);
+        // A stale empty-epoch streak doesn't carry into the first advance
+        assert_eq!(state.consecutive_empty_epochs, 0);
+        assert_eq!((state.best_hash, state.best_hash_miner), (NO_BEST_HASH, Pubkey::default()));
+
+        // Starts inside raised difficulty bounds
+        state.min_difficulty = INITIAL_DIFFICULTY + 4;
+        state.reset([4u8; 32], 1_000);
+        assert_eq!(state.difficulty, INITIAL_DIFFICULTY + 4);
+        assert_eq!(state.difficulty_ema, (INITIAL_DIFFICULTY + 4) * DIFFICULTY_EMA_ONE);
+        assert_eq!(state.health_failures(&state.mint) & HEALTH_DIFFICULTY_RANGE, 0);
+    }
+
+    #[test]
+    fn test_migrate_unversioned_state() {
+        let mut state = sample_state();
+        state.pending_crank_authority = Some(Pubkey::new_unique());
+        state.pending_governance = Some(Pubkey::new_unique());
+        state.next_challenge_seed = Some([9u8; 32]);
+        let space = 8 + MineState::INIT_SPACE;
+        assert_eq!(space, 821);
+        let mut data = vec![0u8; space];
+        state.try_serialize(&mut &mut data[..]).unwrap();
+
+        // A pre-version account: nothing after pending_governance, then zero-grown by realloc
+        let appended = 1 + 3 * 8 + 1 + 5 * 8 + 1 + 8 + 8 + 2 + 8 + 1 + 1 + 4 * 8 + 1 + 8 + 5 + 2 + 4 + 2 + 1 + 2 + 2 + 8 + 32 + 32 + 1; // version and the fields after it
+        data[space - appended..].fill(0);
+        let mut old = MineState::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(old.version, 0);
+        old.governance = Pubkey::default();
+        old.max_solutions_per_miner = 0;
+        old.migrate();
+        assert_eq!(old.version, SCHEMA_VERSION);
+        assert_eq!(old.governance, state.crank_authority);
+        assert_eq!(old.max_solutions_per_miner, 1);
+        assert_eq!(old.difficulty_bounds(), DifficultyBounds::DEFAULT);
+        assert_eq!(old.word_count_thresholds, words::DEFAULT_WORD_COUNT_THRESHOLDS);
+        assert_eq!(old.effective_target(), TARGET_SOLUTIONS);
+        // Text rules added later start switched off rather than tightening on migration
+        assert_eq!(old.ext_rules(), TextRulesExt::BASE);
+        assert_eq!(old.word_reward_bps, 0);
+        assert_eq!(old.submit_cutoff_bps, 0);
+        assert!(!old.reject_repeat_hash);
+        assert_eq!(old.max_reported_solutions, 0);
+        assert_eq!(old.best_hash, NO_BEST_HASH);
+        assert_eq!(old.epoch_number, state.epoch_number);
+        assert_eq!(old.next_challenge_seed, state.next_challenge_seed);
+
+        // Mutating instructions (claim among them) refuse the old layout until it is migrated
+        let mut stale = MineState::try_deserialize(&mut &data[..]).unwrap();
+        assert!(!stale.is_current());
+        stale.migrate();
+        assert!(stale.is_current());
+        assert!(state.is_current());
+
+        // Fields that were already set survive
+        let mut current = sample_state();
+        let governance = current.governance;
+        current.claim_expiry_epochs = 77;
+        current.migrate();
+        assert_eq!(current.governance, governance);
+        assert_eq!(current.claim_expiry_epochs, 77);
+    }
+
+    /// `state` as an account written at `version`, which lacks the last
+    /// `appended` bytes: realloc grows it back with zeros.
+    fn old_layout(state: &MineState, version: u8, appended: usize) -> MineState {
+        let mut state = state.clone();
+        state.version = version;
+        let mut data = Vec::new();
+        state.try_serialize(&mut data).unwrap();
+        let len = data.len();
+        data[len - appended..].fill(0);
+        data.resize(8 + MineState::INIT_SPACE, 0);
+        MineState::try_deserialize(&mut &data[..]).unwrap()
+    }
+
+    #[test]
+    fn test_migrate_v14_state() {
+        let mut state = sample_state();
+        state.max_digit_pct = 7;
+        state.word_reward_bps = 250;
+        state.min_vowel_pct = 20;
+        state.submit_cutoff_bps = 500;
+
+        // v14 ended at word_reward_bps: the vowel and space bands (v15) came later
+        let mut old = old_layout(&state, 14, 4 + 2 + 1 + 2 + 2 + 8 + 32 + 32 + 1);
+        old.migrate();
+        assert_eq!(old.word_reward_bps, 250);
+        assert_eq!(old.max_digit_pct, 7);
+        let base = TextRulesExt::BASE;
+        assert_eq!((old.min_vowel_pct, old.max_vowel_pct), (base.min_vowel_pct, base.max_vowel_pct));
+        assert_eq!((old.min_space_pct, old.max_space_pct), (base.min_space_pct, base.max_space_pct));
+        assert_eq!(old.submit_cutoff_bps, 0);
+        assert!(old.ext_rules().is_valid());
+    }
+
+    #[test]
+    fn test_migrate_v17_state() {
+        let mut state = sample_state();
+        state.submit_cutoff_bps = 500;
+        state.reject_repeat_hash = true;
+        state.min_unique_bytes = 40;
+        state.min_sentence_unique_bytes = 12;
+
+        // v17 ended at reject_repeat_hash: the byte-diversity floors (v18) came later
+        let mut old = old_layout(&state, 17, 2 + 2 + 8 + 32 + 32 + 1);
+        old.migrate();
+        assert_eq!(old.submit_cutoff_bps, 500);
+        assert!(old.reject_repeat_hash);
+        assert_eq!(old.min_unique_bytes, TextRulesExt::BASE.min_unique_bytes);
+        assert_eq!(old.min_sentence_unique_bytes, 0);
+        assert!(old.ext_rules().is_valid());
+    }
+
+    #[test]
+    fn test_migrate_v18_state() {
+        let mut state = sample_state();
+        state.submit_cutoff_bps = 500;
+        state.reject_repeat_hash = true;
+        state.min_sentence_unique_bytes = 12;
+        state.bytes_per_extra_match = 400;
+        state.max_reported_solutions = 9;
+
+        // v18 ended at min_sentence_unique_bytes: bytes_per_extra_match (v19) onwards came later
+        let mut old = old_layout(&state, 18, 2 + 8 + 32 + 32 + 1);
+        old.migrate();
+        assert_eq!(old.submit_cutoff_bps, 500);
+        assert!(old.reject_repeat_hash);
+        assert_eq!(old.min_sentence_unique_bytes, 12);
+        assert_eq!(old.bytes_per_extra_match, 0);
+        assert_eq!(old.max_reported_solutions, 0);
+        assert_eq!(old.best_hash, NO_BEST_HASH);
+    }
+
+    #[test]
+    fn test_migrate_v21_state() {
+        let mut state = sample_state();
+        state.best_hash = [7u8; 32];
+        state.best_hash_miner = Pubkey::new_unique();
+        state.require_balanced_quotes = true;
+
+        // v21 ended at best_hash_miner; require_balanced_quotes came after it
+        let mut old = old_layout(&state, 21, 1);
+        assert!(!old.is_current());
+        old.migrate();
+        assert!(old.is_current());
+        assert_eq!(old.best_hash, state.best_hash);
+        assert_eq!(old.best_hash_miner, state.best_hash_miner);
+        assert!(!old.require_balanced_quotes);
+        assert_eq!(old.ext_rules(), TextRulesExt { require_balanced_quotes: false, ..state.ext_rules() });
+    }
+
+    #[test]
+    fn test_blocked_miner() {
+        let key = Pubkey::new_unique();
+        let mut data = vec![0u8; 8 + BlockedMiner::INIT_SPACE];
+        let (mut live, mut closed, mut empty) = (1_000_000u64, 0u64, 0u64);
+        let blocked = AccountInfo::new(&key, false, false, &mut live, &mut data, &crate::ID, false, 0);
+        assert!(is_blocked(&blocked));
+
+        // Unblocked: close drains the lamports (and later reassigns to the system program)
+        let mut d = vec![];
+        let unblocked = AccountInfo::new(&key, false, false, &mut closed, &mut d, &crate::ID, false, 0);
+        assert!(!is_blocked(&unblocked));
+        let system = system_program::ID;
+        let mut d = vec![];
+        let never = AccountInfo::new(&key, false, false, &mut empty, &mut d, &system, false, 0);
+        assert!(!is_blocked(&never));
+    }
+
+    #[test]
+    fn test_blocked_miner_can_claim() {
+        // A block only stops new submits: Claim takes no BlockedMiner account,
+        // so a blocked miner's earned solution still passes claim's account checks
+        let miner = Pubkey::new_unique();
+        let (blocked_key, _) = Pubkey::find_program_address(&[b"blocked", miner.as_ref()], &crate::ID);
+        let mut blocked_data = vec![0u8; 8 + BlockedMiner::INIT_SPACE];
+        let mut blocked_lamports = 1_000_000u64;
+        let blocked = AccountInfo::new(&blocked_key, false, false, &mut blocked_lamports, &mut blocked_data, &crate::ID, false, 0);
+        assert!(is_blocked(&blocked));
+
+        let (state_key, state_bump) = Pubkey::find_program_address(&[b"mine_state"], &crate::ID);
+        let mut state = sample_state();
+        state.bump = state_bump;
+        let mut state_data = Vec::new();
+        state.try_serialize(&mut state_data).unwrap();
+
+        let (epoch, index) = (state.epoch_number - 1, 0u64);
+        let (solution_key, solution_bump) = Pubkey::find_program_address(
+            &[b"solution", miner.as_ref(), &epoch.to_le_bytes(), &index.to_le_bytes()],
+            &crate::ID,
+        );
+        let solution = Solution {
+            miner,
+            recipient: Pubkey::default(),
+            epoch,
+            nonce: 1,
+            hash: [0u8; 32],
+            bump: solution_bump,
+            deposit: 0,
+            difficulty: 8,
+            slot: 100,
+            slot_hash: [0u8; 32],
+            text_hash: [0u8; 32],
+            index,
+            required_word_count: 3,
+            reward_amount: 1_000,
+        };
+        let mut solution_data = Vec::new();
+        solution.try_serialize(&mut solution_data).unwrap();
+
+        let (vesting_key, vesting_bump) = Pubkey::find_program_address(&[b"vesting", miner.as_ref()], &crate::ID);
+        let vesting = VestingAccount { miner, locked: 0, unlocked: 0, last_update: 0, bump: vesting_bump };
+        let mut vesting_data = Vec::new();
+        vesting.try_serialize(&mut vesting_data).unwrap();
+
+        // Absent optional accounts are passed as the program id
+        let (program, system) = (crate::ID, system_program::ID);
+        let mut lamports = [1_000_000u64; 11];
+        let [l0, l1, l2, l3, l4, l5, l6, l7, l8, l9, l10] = &mut lamports;
+        let mut empty: [Vec<u8>; 8] = Default::default();
+        let [d0, d1, d2, d3, d4, d5, d6, d7] = &mut empty;
+        let infos = [
+            AccountInfo::new(&state_key, false, true, l0, &mut state_data, &crate::ID, false, 0),
+            AccountInfo::new(&solution_key, false, true, l1, &mut solution_data, &crate::ID, false, 0),
+            AccountInfo::new(&vesting_key, false, true, l2, &mut vesting_data, &crate::ID, false, 0),
+            AccountInfo::new(&program, false, false, l3, d0, &system, true, 0),
+            AccountInfo::new(&program, false, false, l4, d1, &system, true, 0),
+            AccountInfo::new(&miner, false, true, l5, d2, &system, false, 0),
+            AccountInfo::new(&miner, true, true, l6, d3, &system, false, 0),
+            AccountInfo::new(&program, false, false, l7, d4, &system, true, 0),
+            AccountInfo::new(&program, false, false, l8, d5, &system, true, 0),
+            AccountInfo::new(&program, false, false, l9, d6, &system, true, 0),
+            AccountInfo::new(&system, false, false, l10, d7, &system, true, 0),
+        ];
+        let mut remaining = &infos[..];
+        let mut bumps = ClaimBumps::default();
+        assert!(Claim::try_accounts(&crate::ID, &mut remaining, &[], &mut bumps, &mut Default::default()).is_ok());
+    }
+
+    #[test]
+    fn test_difficulty_trend() {
+        let mut history = EpochHistory {
+            head: 0,
+            len: 0,
+            records: [EpochRecord::default(); EPOCH_HISTORY_LEN],
+            bump: 255,
+        };
+        let mut state = sample_state();
+        state.difficulty = 20;
+
+        // No history yet: nothing to compare against
+        let report = DifficultyTrend::new(&state, &history);
+        assert_eq!((report.epochs, report.average_x100, report.trend), (0, 0, Trend::Stable));
+
+        // Old epochs at 40 fall out of the window; the last 8 average 16.5
+        for epoch in 0..10 {
+            history.push(EpochRecord { epoch, difficulty: 40, ..Default::default() });
+        }
+        for (i, difficulty) in [15, 18, 15, 18, 15, 18, 15, 18].into_iter().enumerate() {
+            history.push(EpochRecord { epoch: 10 + i as u64, difficulty, ..Default::default() });
+        }
+        let report = DifficultyTrend::new(&state, &history);
+        assert_eq!((report.epochs, report.average_x100, report.trend), (8, 1650, Trend::Rising));
+
+        state.difficulty = 12;
+        assert_eq!(DifficultyTrend::new(&state, &history).trend, Trend::Falling);
+        // Within half a bit either way reads as stable
+        for difficulty in [16, 17] {
+            state.difficulty = difficulty;
+            assert_eq!(DifficultyTrend::new(&state, &history).trend, Trend::Stable);
+        }
+        assert_eq!(Trend::of(18, 33, 2), Trend::Rising);
+        assert_eq!(Trend::of(15, 33, 2), Trend::Falling);
+    }
+
+    #[test]
+    fn test_epoch_history_ring() {
+        let mut history = EpochHistory {
+            head: 0,
+            len: 0,
+            records: [EpochRecord::default(); EPOCH_HISTORY_LEN],
+            bump: 255,
+        };
+        let record = |epoch: u64| EpochRecord { epoch, difficulty: 8 + epoch % 3, solution_count: epoch * 2, start_time: 600 * epoch as i64 };
+        for epoch in 0..5 {
+            history.push(record(epoch));
+        }
+        assert_eq!((history.head, history.len), (5, 5));
+        assert_eq!(history.ordered().map(|r| r.epoch).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+
+        // Wrapping overwrites the oldest and keeps readers in order
+        for epoch in 5..40 {
+            history.push(record(epoch));
+        }
+        assert_eq!(history.len as usize, EPOCH_HISTORY_LEN);
+        assert_eq!(history.head, (40 % EPOCH_HISTORY_LEN) as u8);
+        let epochs: Vec<u64> = history.ordered().map(|r| r.epoch).collect();
+        assert_eq!(epochs, (8..40).collect::<Vec<_>>());
+        assert_eq!(history.ordered().last(), Some(&record(39)));
+        assert_eq!(8 + EpochHistory::INIT_SPACE, 1035);
+
+        // Paging: whole pages come back in order and match the ring
+        let mut dumped = Vec::new();
+        for start in (8..40).step_by(MAX_HISTORY_PAGE as usize) {
+            let page = EpochHistoryPage::new(&history, start, MAX_HISTORY_PAGE);
+            assert_eq!(page.oldest_epoch, Some(8));
+            assert_eq!(page.records.len() as u64, MAX_HISTORY_PAGE);
+            dumped.extend(page.records);
+        }
+        assert_eq!(dumped, history.ordered().copied().collect::<Vec<_>>());
+        // A range straddling the evicted epochs returns only what's stored
+        let page = EpochHistoryPage::new(&history, 4, 6);
+        assert_eq!(page.records, vec![record(8), record(9)]);
+        assert!(EpochHistoryPage::new(&history, 40, 5).records.is_empty());
+    }
+
+    #[test]
+    fn test_difficulty_preview_matches_advance() {
+        let mut state = sample_state();
+        state.min_hash_weight = 2;
+        let mut best = [0u8; 32];
+        best[4] = 0x01; // 39 leading zero bits
+        let now = state.epoch_end_time + 5;
+        for count in [0u64, 12, 50, 400] {
+            let bonus = min_hash_bonus(&best, state.difficulty, count, state.min_hash_weight);
+            let expected = smooth_difficulty(state.difficulty_ema, state.difficulty, count, bonus, &DifficultyBounds::DEFAULT);
+            assert_eq!(state.adjusted_difficulty(count, now, Some(&best)), expected);
+            assert_eq!(
+                state.adjusted_difficulty(count, now, None),
+                smooth_difficulty(state.difficulty_ema, state.difficulty, count, 0, &DifficultyBounds::DEFAULT)
+            );
+        }
+
+        // With normalization on, a late advance previews the scaled count
+        state.normalize_overrun = true;
+        let late = state.epoch_start_time + 2 * EPOCH_DURATION;
+        assert_eq!(
+            state.adjusted_difficulty(140, late, None),
+            smooth_difficulty(state.difficulty_ema, state.difficulty, 70, 0, &DifficultyBounds::DEFAULT)
+        );
+    }
+
+    #[test]
+    fn test_empty_epoch_grace() {
+        let mut state = sample_state();
+        let now = state.epoch_end_time;
+        let steady = state.difficulty * DIFFICULTY_EMA_ONE;
+        state.difficulty_ema = steady;
+        let ema_drop = |state: &MineState| steady - state.adjusted_difficulty(0, now, None).0;
+        // Off: an empty epoch folds the full max_adj step into the EMA
+        let full = ema_drop(&state);
+        assert_eq!(full, MAX_DIFFICULTY_ADJ * DIFFICULTY_EMA_ONE / DIFFICULTY_EMA_WINDOW);
+
+        // Grace of 3: the first two empty epochs in a row step by 1 only...
+        state.empty_epoch_grace = 3;
+        for streak in 0..2 {
+            state.consecutive_empty_epochs = streak;
+            assert_eq!(ema_drop(&state), DIFFICULTY_EMA_ONE / DIFFICULTY_EMA_WINDOW);
+            assert!(ema_drop(&state) < full);
+        }
+        // ...and the third gets the full decrease
+        state.consecutive_empty_epochs = 2;
+        assert_eq!(ema_drop(&state), full);
+        state.consecutive_empty_epochs = 9;
+        assert_eq!(ema_drop(&state), full);
+
+        // A long empty run still brings difficulty down, never faster than without grace
+        let run = |grace: u8, epochs: usize| {
+            let mut s = sample_state();
+            s.difficulty_ema = steady;
+            s.empty_epoch_grace = grace;
+            for _ in 0..epochs {
+                (s.difficulty_ema, s.difficulty) = s.adjusted_difficulty(0, now, None);
+                s.consecutive_empty_epochs += 1;
+            }
+            s.difficulty
+        };
+        for epochs in [1, 4, 12] {
+            assert!(run(3, epochs) >= run(0, epochs));
+        }
+        assert!(run(3, 12) < state.difficulty);
+        assert_eq!(state.feature_flags() & FEATURE_EMPTY_EPOCH_GRACE, FEATURE_EMPTY_EPOCH_GRACE);
+    }
+
+    #[test]
+    fn test_target_scales_with_miners() {
+        // 0.1 extra solutions targeted per miner, capped at 200
+        assert_eq!(effective_target(0, 1_000, 200), TARGET_SOLUTIONS);
+        assert_eq!(effective_target(500, 1_000, 200), 100);
+        assert_eq!(effective_target(100_000, 1_000, 200), 200);
+        assert_eq!(effective_target(500, 0, 200), TARGET_SOLUTIONS);
+        assert!(effective_target(1_000, 1_000, 200) > effective_target(500, 1_000, 200));
+
+        // 100 solutions step difficulty up against the fixed target of 50...
+        let bounds = DifficultyBounds::DEFAULT;
+        let d = 17;
+        assert!(step_difficulty(d, scale_to_target(100, TARGET_SOLUTIONS), &bounds) > d);
+        // ...but are on target once 500 miners push the target to 100
+        assert_eq!(step_difficulty(d, scale_to_target(100, 100), &bounds), d);
+        assert!(step_difficulty(d, scale_to_target(50, 100), &bounds) < d);
+
+        // advance_epoch's decision sees the scaled target
+        let mut state = sample_state();
+        let now = state.epoch_end_time;
+        let (fixed_ema, _) = state.adjusted_difficulty(100, now, None);
+        state.target_per_miner_bps = 1_000;
+        state.max_target_solutions = 200;
+        state.unique_miners = 500;
+        assert_eq!(state.effective_target(), 100);
+        let (scaled_ema, _) = state.adjusted_difficulty(100, now, None);
+        assert!(scaled_ema < fixed_ema);
+        let steady = state.difficulty * DIFFICULTY_EMA_ONE;
+        state.difficulty_ema = steady;
+        assert_eq!(state.adjusted_difficulty(100, now, None), (steady, state.difficulty));
+        assert_eq!(state.feature_flags() & FEATURE_TARGET_SCALING, FEATURE_TARGET_SCALING);
+    }
+
+    #[test]
+    fn test_advance_bonus() {
+        let state = sample_state();
+        let bonus = state.initial_reward / MAX_ADVANCE_BONUS_DIVISOR;
+        // Only with permissionless advance on
+        assert_eq!(advance_bonus(false, bonus, 0, state.max_supply), 0);
+        assert_eq!(advance_bonus(true, bonus, state.total_supply, state.max_supply), bonus);
+        // Capped by what is left under the supply cap
+        assert_eq!(advance_bonus(true, bonus, state.max_supply - 7, state.max_supply), 7);
+        assert_eq!(advance_bonus(true, bonus, state.max_supply, state.max_supply), 0);
+        // The setter's bound is a tenth of the initial reward: 2,500 CRB at 3 decimals
+        assert_eq!(bonus, 2_500_000);
+    }
+
+    #[test]
+    fn test_health_check() {
+        let (mint, _) = Pubkey::find_program_address(&[b"mint"], &crate::ID);
+        let mut state = sample_state();
+        state.mint = mint;
+        assert_eq!(state.health_failures(&mint), 0);
+
+        // Each corruption trips its own bit
+        type Corrupt = fn(&mut MineState);
+        let cases: [(Corrupt, u32); 9] = [
+            (|s| s.difficulty = s.max_difficulty + 1, HEALTH_DIFFICULTY_RANGE),
+            (|s| s.max_difficulty_adj = 0, HEALTH_DIFFICULTY_BOUNDS),
+            (|s| s.epoch_end_time = s.epoch_start_time, HEALTH_EPOCH_TIMES),
+            (|s| s.total_supply = s.max_supply + 1, HEALTH_SUPPLY),
+            (|s| s.mint = Pubkey::new_unique(), HEALTH_MINT),
+            (|s| s.max_digit_pct = 101, HEALTH_TEXT_RULES),
+            (|s| s.version = SCHEMA_VERSION - 1, HEALTH_SCHEMA),
+            (|s| s.crank_count = MAX_CRANKS as u8 + 1, HEALTH_CRANK_SET),
+            (|s| s.governance = Pubkey::default(), HEALTH_GOVERNANCE),
+        ];
+        for (corrupt, bit) in cases {
+            let mut bad = state.clone();
+            corrupt(&mut bad);
+            assert_eq!(bad.health_failures(&mint), bit);
+        }
+
+        // Several at once are all reported
+        state.total_supply = state.max_supply + 1;
+        state.version = 0;
+        assert_eq!(state.health_failures(&mint), HEALTH_SUPPLY | HEALTH_SCHEMA);
+    }
+
+    #[test]
+    fn test_last_hash_rejects_repeat() {
+        let mut last = LastHash { hash: [0u8; 32], bump: 255 };
+        let (first, second) = ([7u8; 32], [8u8; 32]);
+        assert!(last.record(first));
+        // The same hash in a later epoch (e.g. a repeated seed) is refused and not re-stored
+        assert!(!last.record(first));
+        assert_eq!(last.hash, first);
+        assert!(last.record(second));
+        // Only the latest hash is kept: an older one is accepted again
+        assert!(last.record(first));
+
+        let mut state = sample_state();
+        state.chain_mode = false;
+        assert_eq!(state.feature_flags(), 0);
+        state.reject_repeat_hash = true;
+        assert_eq!(state.feature_flags(), FEATURE_REPEAT_HASH_CHECK);
+    }
+
+    #[test]
+    fn test_plausible_solution_count() {
+        let span = EPOCH_DURATION;
+        // Off: only the physical rate bounds the count
+        assert!(plausible_solution_count(1_000_000, span, 0));
+        assert!(plausible_solution_count(MAX_SUBMITS_PER_SECOND * 600, span, 0));
+        assert!(!plausible_solution_count(MAX_SUBMITS_PER_SECOND * 600 + 1, span, 0));
+        // The configured ceiling, inclusive
+        assert!(plausible_solution_count(0, span, DEFAULT_MAX_REPORTED_SOLUTIONS));
+        assert!(plausible_solution_count(DEFAULT_MAX_REPORTED_SOLUTIONS, span, DEFAULT_MAX_REPORTED_SOLUTIONS));
+        assert!(!plausible_solution_count(DEFAULT_MAX_REPORTED_SOLUTIONS + 1, span, DEFAULT_MAX_REPORTED_SOLUTIONS));
+        assert!(!plausible_solution_count(u64::MAX, span, DEFAULT_MAX_REPORTED_SOLUTIONS));
+        // A degenerate span admits nothing but an empty epoch
+        assert!(plausible_solution_count(0, 0, 0));
+        assert!(!plausible_solution_count(1, -5, 0));
+    }
+
+    #[test]
+    fn test_best_hash_record() {
+        let mut state = sample_state();
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let hash = |zero_bytes: usize, tail: u8| {
+            let mut h = [tail; 32];
+            h[..zero_bytes].fill(0);
+            h
+        };
+
+        // Any hash beats the empty record, then each harder one beats the last
+        assert!(state.record_best_hash(&hash(1, 0x80), &alice));
+        assert!(state.record_best_hash(&hash(2, 0x80), &bob));
+        assert_eq!((state.best_hash, state.best_hash_miner), (hash(2, 0x80), bob));
+        assert!(state.record_best_hash(&hash(2, 0x10), &alice));
+        assert_eq!(leading_zero_bits(&state.best_hash), 19);
+
+        // Weaker or equal hashes leave the record and its miner alone
+        assert!(!state.record_best_hash(&hash(1, 0x01), &bob));
+        assert!(!state.record_best_hash(&hash(2, 0x10), &bob));
+        assert_eq!((state.best_hash, state.best_hash_miner), (hash(2, 0x10), alice));
+    }
+
+    #[test]
+    fn test_feature_flags() {
+        let mut state = sample_state();
+        state.chain_mode = false;
+        assert_eq!(state.feature_flags(), 0);
+        state.chain_mode = true;
+        state.counter_shards = 4;
+        state.claim_tip_bps = 50;
+        assert_eq!(state.feature_flags(), FEATURE_CHAIN_MODE | FEATURE_COUNTER_SHARDS | FEATURE_CLAIM_TIP);
+        // A boost needs both a length and a multiplier above 1×
+        state.boost_epochs = 100;
+        assert_eq!(state.feature_flags() & FEATURE_EARLY_BOOST, 0);
+        state.boost_multiplier_bps = 15_000;
+        assert_ne!(state.feature_flags() & FEATURE_EARLY_BOOST, 0);
+        state.chain_mode = false;
+        assert_eq!(state.feature_flags() & FEATURE_CHAIN_MODE, 0);
+    }
+
+    #[test]
+    fn test_burn_frees_supply() {
+        let mut state = sample_state();
+        state.total_supply = state.max_supply;
+        assert_eq!(claim_reward(0, state.initial_reward, 0, BPS_ONE.into(), state.total_supply, state.max_supply), 0);
+        state.record_burn(1_000).unwrap();
+        assert_eq!(state.total_supply, state.max_supply - 1_000);
+        // Headroom is available to claims again
+        assert_eq!(claim_reward(0, state.initial_reward, 0, BPS_ONE.into(), state.total_supply, state.max_supply), 1_000);
+        // Underflow is an error and leaves the supply untouched
+        assert!(state.record_burn(state.max_supply).is_err());
+        assert_eq!(state.total_supply, state.max_supply - 1_000);
+    }
+
+    #[test]
+    fn test_emission_end() {
+        let mut state = sample_state();
+        // Last claim interval with a nonzero reward
+        let halvings = 64 - state.initial_reward.leading_zeros() as u64;
+        state.total_mined = halvings * HALVING_INTERVAL - 2;
+        assert!(calculate_reward(state.total_mined, state.initial_reward) > 0);
+        state.record_claim(1).unwrap();
+        assert!(!state.mark_emission_end());
+        assert!(!state.emission_ended);
+        // The claim that halves the reward to 0 flips the flag (and emits) once
+        state.record_claim(1).unwrap();
+        assert_eq!(calculate_reward(state.total_mined, state.initial_reward), 0);
+        assert!(state.mark_emission_end());
+        assert!(state.emission_ended);
+        state.record_claim(0).unwrap();
+        assert!(!state.mark_emission_end());
+        assert!(state.emission_ended);
+    }
+
+    #[test]
+    fn test_delegated_claim_in_chain_mode() {
+        let (miner, keeper) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(claim_caller_allowed(false, &keeper, &miner));
+        assert!(claim_caller_allowed(false, &miner, &miner));
+        // A keeper's claim would move the miner's chain tip under it
+        assert!(!claim_caller_allowed(true, &keeper, &miner));
+        assert!(claim_caller_allowed(true, &miner, &miner));
+    }
+
+    #[test]
+    fn test_claim_tip() {
+        assert_eq!(claim_tip(50_000, 0), 0);
+        assert_eq!(claim_tip(50_000, 100), 500);
+        assert_eq!(claim_tip(50_000, MAX_CLAIM_TIP_BPS), 5_000);
+        assert_eq!(claim_tip(99, 100), 0);
+        // No overflow on large rewards
+        assert_eq!(claim_tip(u64::MAX, 10_000), u64::MAX);
+        // The tip never exceeds the reward at or below the cap
+        for reward in [1u64, 7, 1_000_003] {
+            assert!(claim_tip(reward, MAX_CLAIM_TIP_BPS) <= reward);
+        }
+    }
+
+    #[test]
+    fn test_crank_set() {
+        let mut state = sample_state();
+        let ops: Vec<Pubkey> = (0..MAX_CRANKS).map(|_| Pubkey::new_unique()).collect();
+        let primary = state.crank_authority;
+        assert!(state.is_crank(&primary));
+        assert!(!state.is_crank(&ops[0]));
+        // Governance appoints cranks but is not one itself
+        assert!(!state.is_crank(&state.governance));
+
+        for op in &ops {
+            state.add_crank(*op).unwrap();
+        }
+        assert!(ops.iter().all(|op| state.is_crank(op)));
+        assert!(state.add_crank(Pubkey::new_unique()).is_err());
+        assert!(state.add_crank(ops[0]).is_err());
+
+        // Removing from the middle keeps the rest authorized
+        state.remove_crank(&ops[1]).unwrap();
+        assert!(!state.is_crank(&ops[1]));
+        assert_eq!(state.cranks().len(), MAX_CRANKS - 1);
+        assert!(ops.iter().enumerate().filter(|(i, _)| *i != 1).all(|(_, op)| state.is_crank(op)));
+        assert!(state.remove_crank(&ops[1]).is_err());
+        // Default (empty-slot) key is never a crank
+        assert!(!state.is_crank(&Pubkey::default()));
+    }
+
+    /// Run `counts` through `advance_epoch`'s difficulty update from difficulty 20.
+    fn drive(counts: impl Iterator<Item = u64>) -> Vec<u64> {
+        let (mut ema, mut difficulty) = (20 * DIFFICULTY_EMA_ONE, 20);
+        counts
+            .map(|c| {
+                (ema, difficulty) = smooth_difficulty(ema, difficulty, c, 0, &DifficultyBounds::DEFAULT);
+                difficulty
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_difficulty_ema_converges() {
+        // Alternating surplus / shortfall: the raw step swings 20 ↔ 23 forever
+        let alternating = (0..60).map(|i| if i % 2 == 0 { 400 } else { 6 });
+        let mut raw = 20;
+        let stepped: Vec<u64> = alternating
+            .clone()
+            .map(|c| {
+                raw = step_difficulty(raw, c, &DifficultyBounds::DEFAULT);
+                raw
+            })
+            .collect();
+        assert!(stepped[50..].windows(2).all(|w| w[0].abs_diff(w[1]) == 3));
+        // The EMA settles on one value
+        let smoothed = drive(alternating);
+        let tail = &smoothed[40..];
+        assert!(tail.iter().max().unwrap() - tail.iter().min().unwrap() <= 1, "{:?}", tail);
+    }
+
+    #[test]
+    fn test_difficulty_ema_tracks_trends() {
+        // Sustained surplus keeps raising difficulty; sustained drought lowers it
+        let up = drive(core::iter::repeat_n(400, 30));
+        assert!(up.windows(2).all(|w| w[1] >= w[0]));
+        assert!(up[29] >= 30);
+        let down = drive(core::iter::repeat_n(0, 60));
+        assert!(down.windows(2).all(|w| w[1] <= w[0]));
+        assert_eq!(down[59], MIN_DIFFICULTY);
+        // On target, nothing moves
+        assert!(drive(core::iter::repeat_n(TARGET_SOLUTIONS, 10)).iter().all(|&d| d == 20));
+    }
+
+    #[test]
+    fn test_difficulty_bounds() {
+        assert!(DifficultyBounds::DEFAULT.is_valid());
+        let invalid = [(0, 10, 1), (10, 10, 1), (12, 10, 1), (4, 257, 1), (4, 250, 0)];
+        for (min, max, max_adj) in invalid {
+            assert!(!DifficultyBounds { min, max, max_adj }.is_valid(), "{min} {max} {max_adj}");
+        }
+
+        // A raised floor and a 1-bit step: droughts stop at 12, one bit per epoch
+        let bounds = DifficultyBounds { min: 12, max: 40, max_adj: 1 };
+        assert_eq!(step_difficulty(20, 0, &bounds), 19);
+        assert_eq!(step_difficulty(12, 0, &bounds), 12);
+        assert_eq!(step_difficulty(40, 4_000, &bounds), 40);
+        let (mut ema, mut difficulty) = (20 * DIFFICULTY_EMA_ONE, 20);
+        for _ in 0..80 {
+            let prev = difficulty;
+            (ema, difficulty) = smooth_difficulty(ema, difficulty, 0, 0, &bounds);
+            assert!(prev - difficulty <= 1);
+        }
+        assert_eq!(difficulty, 12);
+
+        // A floor raised above the current difficulty applies at the next advance
+        let mut state = sample_state();
+        state.min_difficulty = 30;
+        assert_eq!(state.adjusted_difficulty(TARGET_SOLUTIONS, state.epoch_end_time, None).1, 30);
+    }
+
+    /// A hash with exactly `bits` leading zero bits.
+    fn hash_with_zero_bits(bits: usize) -> [u8; 32] {
+        let mut h = [0xFFu8; 32];
+        h[..bits / 8].fill(0);
+        h[bits / 8] = 0xFF >> (bits % 8);
+        h
+    }
+
+    #[test]
+    fn test_min_hash_raises_difficulty() {
+        assert_eq!(leading_zero_bits(&hash_with_zero_bits(0)), 0);
+        assert_eq!(leading_zero_bits(&hash_with_zero_bits(27)), 27);
+        assert_eq!(leading_zero_bits(&[0u8; 32]), 256);
+
+        // 50 solutions at difficulty 20: a best hash around 26 bits is expected
+        assert_eq!(min_hash_bonus(&hash_with_zero_bits(26), 20, 50, 3), 0);
+        assert_eq!(min_hash_bonus(&hash_with_zero_bits(28), 20, 50, 3), 2);
+        // A very low hash is capped by the weight, and weight 0 disables it
+        assert_eq!(min_hash_bonus(&hash_with_zero_bits(60), 20, 50, 3), 3);
+        assert_eq!(min_hash_bonus(&hash_with_zero_bits(60), 20, 50, 0), 0);
+
+        // On-target counts: no change without a bonus, steady climb with one
+        let (mut ema, mut plain) = (20 * DIFFICULTY_EMA_ONE, 20);
+        let (mut ema_b, mut boosted) = (ema, 20);
+        for _ in 0..16 {
+            (ema, plain) = smooth_difficulty(ema, plain, TARGET_SOLUTIONS, 0, &DifficultyBounds::DEFAULT);
+            let bonus = min_hash_bonus(&hash_with_zero_bits(60), boosted, TARGET_SOLUTIONS, 3);
+            (ema_b, boosted) = smooth_difficulty(ema_b, boosted, TARGET_SOLUTIONS, bonus, &DifficultyBounds::DEFAULT);
+        }
+        assert_eq!(plain, 20);
+        assert!(boosted > 20 && boosted <= 20 + 16 * MAX_MIN_HASH_WEIGHT as u64);
+    }
+
+    #[test]
+    fn test_rapid_advances_throttled() {
+        let first = 1_000_000;
+        // An epoch that ended long ago can be advanced right away...
+        assert!(!advance_throttled(first, first - EPOCH_DURATION));
+        // ...but a second advance in the same half-minute is rejected, even if the epoch has ended
+        assert!(!epoch_active(first + 1, first));
+        assert!(advance_throttled(first + 1, first));
+        assert!(advance_throttled(first + MIN_ADVANCE_INTERVAL - 1, first));
+        assert!(!advance_throttled(first + MIN_ADVANCE_INTERVAL, first));
+    }
+
+    #[test]
+    fn test_claim_when_crank_dead() {
+        let end = 1_000_000 + EPOCH_DURATION;
+        // Crank dead: epoch 42 ended by the clock but was never advanced
+        let (current, now) = (42, end + 3600);
+        assert!(epoch_claimable(42, current, now, end, false));
+        assert!(!epoch_claimable(42, current, now, end, true));
+        // Before the end time neither policy pays out
+        assert!(!epoch_claimable(42, current, end - 1, end, false));
+        assert!(!epoch_claimable(42, current, end - 1, end, true));
+        // Once advanced, earlier epochs are claimable under both
+        assert!(epoch_claimable(42, 43, now, end, false));
+        assert!(epoch_claimable(42, 43, now, end, true));
+        assert!(!epoch_claimable(44, 43, now, end, false));
+
+        let mut state = sample_state();
+        state.chain_mode = false;
+        state.claim_requires_advance = true;
+        assert_eq!(state.feature_flags(), FEATURE_CLAIM_REQUIRES_ADVANCE);
+    }
+
+    #[test]
+    fn test_extend_current_epoch() {
+        let start = 1_000_000;
+        let end = start + EPOCH_DURATION;
+        let new_end = extended_epoch_end(start, end, 300).unwrap();
+        assert_eq!(new_end, end + 300);
+        // Past the original end: submit_solution still accepts, advance_epoch is not yet eligible
+        let now = end + 60;
+        assert!(!epoch_active(now, end));
+        assert!(epoch_active(now, new_end));
+        // Advance becomes eligible at the new end
+        assert!(!epoch_active(new_end, new_end));
+        // Bounds
+        assert_eq!(extended_epoch_end(start, end, 0), None);
+        assert_eq!(extended_epoch_end(start, end, -60), None);
+        assert_eq!(extended_epoch_end(start, end, MAX_EPOCH_SPAN - EPOCH_DURATION), Some(start + MAX_EPOCH_SPAN));
+        assert_eq!(extended_epoch_end(start, end, MAX_EPOCH_SPAN - EPOCH_DURATION + 1), None);
+        assert_eq!(extended_epoch_end(start, end, i64::MAX), None);
+    }
+
+    #[test]
+    fn test_tampered_solution_rejected_at_claim() {
+        let seed = [7u8; 32];
+        let miner = Pubkey::new_unique();
+        let text = b"claimed text";
+        let nonce = mine(&seed, None, &miner, text, 12);
+        let mut solution = Solution {
+            miner,
+            recipient: miner,
+            epoch: 3,
+            nonce,
+            hash: solution_hash(&seed, &SLOT_HASH, None, &miner, text, nonce),
+            bump: 255,
+            deposit: 0,
+            difficulty: 12,
+            slot: 100,
+            slot_hash: SLOT_HASH,
+            text_hash: text_hash(text),
+            index: 0,
+            required_word_count: 3,
+            reward_amount: 0,
+        };
+        assert!(solution.meets_difficulty());
+        // A flipped bit in the leading zeros no longer meets the recorded difficulty
+        solution.hash[0] |= 0x80;
+        assert!(!solution.meets_difficulty());
+        // Neither does an honest hash whose recorded difficulty was raised
+        solution.hash = solution_hash(&seed, &SLOT_HASH, None, &miner, text, nonce);
+        solution.difficulty = 250;
+        assert!(!solution.meets_difficulty());
+    }
+
+    #[test]
+    fn test_slot_hash_binding() {
+        let seed = [7u8; 32];
+        let miner = Pubkey::new_unique();
+        let text = b"bound text";
+        let nonce = mine(&seed, None, &miner, text, 12);
+        // A nonce ground against one slot hash doesn't carry over to another
+        assert_ne!(
+            solution_hash(&seed, &SLOT_HASH, None, &miner, text, nonce),
+            solution_hash(&seed, &[6u8; 32], None, &miner, text, nonce)
+        );
+    }
+
+    #[test]
+    fn test_text_dedup_key() {
+        let seed = [7u8; 32];
+        let text = b"shared text";
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (na, nb) = (mine(&seed, None, &a, text, 8), mine(&seed, None, &b, text, 8));
+        // Different miners, different solution hashes...
+        assert_ne!(
+            solution_hash(&seed, &SLOT_HASH, None, &a, text, na),
+            solution_hash(&seed, &SLOT_HASH, None, &b, text, nb)
+        );
+        // ...but the same TextSeen PDA, so the second init fails
+        assert_eq!(text_hash(text), text_hash(b"shared text"));
+        assert_ne!(text_hash(text), text_hash(b"shared text."));
+    }
+
+    #[test]
+    fn test_find_slot_hash() {
+        // Sysvar layout: count, then (slot, hash) newest first
+        let mut data = 3u64.to_le_bytes().to_vec();
+        for slot in [30u64, 20, 10] {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[slot as u8; 32]);
+        }
+        assert_eq!(find_slot_hash(&data, 30), Some([30u8; 32]));
+        assert_eq!(find_slot_hash(&data, 10), Some([10u8; 32]));
+        assert_eq!(find_slot_hash(&data, 25), None);
+        assert_eq!(find_slot_hash(&data, 31), None);
+        assert_eq!(find_slot_hash(&data, 5), None);
+        // Truncated data: count claims more entries than are present
+        assert_eq!(find_slot_hash(&data[..8 + 40], 20), None);
+        assert_eq!(find_slot_hash(&[], 30), None);
+
+        assert_eq!(latest_slot_hash(&data), Some([30u8; 32]));
+        assert_eq!(latest_slot_hash(&0u64.to_le_bytes()), None);
+        assert_eq!(latest_slot_hash(&data[..8 + 20]), None);
+    }
+
+    /// Run `WithdrawToVault`'s account checks for `miner`'s vesting, `signer` in
+    /// the miner slot and a vault of `vault_mint` owned by `vault_owner`.
+    fn withdraw_to_vault_checks(
+        miner: Pubkey,
+        signer: Pubkey,
+        is_signer: bool,
+        vault_owner: Pubkey,
+        vault_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        use anchor_lang::solana_program::program_option::COption;
+        use anchor_lang::solana_program::program_pack::Pack;
+        use anchor_spl::token::spl_token::state::{Account as SplAccount, AccountState, Mint as SplMint};
+
+        let (state_key, state_bump) = Pubkey::find_program_address(&[b"mine_state"], &crate::ID);
+        let (vesting_key, vesting_bump) = Pubkey::find_program_address(&[b"vesting", miner.as_ref()], &crate::ID);
+        let (mint_key, _) = Pubkey::find_program_address(&[b"mint"], &crate::ID);
+        let (vault_key, token_id, system) = (Pubkey::new_unique(), token::ID, system_program::ID);
+
+        let mut state = sample_state();
+        state.bump = state_bump;
+        let mut state_data = Vec::new();
+        state.try_serialize(&mut state_data).unwrap();
+        let vesting = VestingAccount { miner, locked: 1_000, unlocked: 0, last_update: 0, bump: vesting_bump };
+        let mut vesting_data = Vec::new();
+        vesting.try_serialize(&mut vesting_data).unwrap();
+        let mut mint_data = vec![0u8; SplMint::LEN];
+        let mint = SplMint {
+            mint_authority: COption::Some(state_key),
+            supply: 0,
+            decimals: 9,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        SplMint::pack(mint, &mut mint_data).unwrap();
+        let mut vault_data = vec![0u8; SplAccount::LEN];
+        let vault = SplAccount {
+            mint: vault_mint.unwrap_or(mint_key),
+            owner: vault_owner,
+            state: AccountState::Initialized,
+            ..Default::default()
+        };
+        SplAccount::pack(vault, &mut vault_data).unwrap();
+
+        let mut lamports = [1_000_000u64; 6];
+        let [l0, l1, l2, l3, l4, l5] = &mut lamports;
+        let (mut signer_data, mut program_data) = (vec![], vec![]);
+        let infos = [
+            AccountInfo::new(&state_key, false, false, l0, &mut state_data, &crate::ID, false, 0),
+            AccountInfo::new(&vesting_key, false, true, l1, &mut vesting_data, &crate::ID, false, 0),
+            AccountInfo::new(&mint_key, false, true, l2, &mut mint_data, &token_id, false, 0),
+            AccountInfo::new(&vault_key, false, true, l3, &mut vault_data, &token_id, false, 0),
+            AccountInfo::new(&signer, is_signer, false, l4, &mut signer_data, &system, false, 0),
+            AccountInfo::new(&token_id, false, false, l5, &mut program_data, &system, true, 0),
+        ];
+        let mut remaining = &infos[..];
+        let mut bumps = WithdrawToVaultBumps::default();
+        WithdrawToVault::try_accounts(&crate::ID, &mut remaining, &[], &mut bumps, &mut Default::default())
+            .map(|_| ())
+    }
+
+    #[test]
+    fn test_withdraw_to_vault() {
+        // A vault owned by another program's PDA: nobody can sign for it, so
+        // only the miner's signature on withdraw_to_vault routes funds there
+        let integrator = Pubkey::new_unique();
+        let (vault_owner, _) = Pubkey::find_program_address(&[b"vault"], &integrator);
+        assert!(!vault_owner.is_on_curve());
+
+        let miner = Pubkey::new_unique();
+        assert!(withdraw_to_vault_checks(miner, miner, true, vault_owner, None).is_ok());
+        // The miner must sign, and nobody else may sign in its place: the
+        // vesting PDA is derived from the signer
+        assert_eq!(
+            withdraw_to_vault_checks(miner, miner, false, vault_owner, None).unwrap_err(),
+            anchor_lang::error::ErrorCode::AccountNotSigner.into()
+        );
+        let other = Pubkey::new_unique();
+        assert_eq!(
+            withdraw_to_vault_checks(miner, other, true, vault_owner, None).unwrap_err(),
+            anchor_lang::error::ErrorCode::ConstraintSeeds.into()
+        );
+        // The vault must hold CRB
+        assert_eq!(
+            withdraw_to_vault_checks(miner, miner, true, vault_owner, Some(Pubkey::new_unique())).unwrap_err(),
+            anchor_lang::error::ErrorCode::ConstraintTokenMint.into()
+        );
+
+        let mut vesting = VestingAccount { miner, locked: 1_000, unlocked: 0, last_update: 0, bump: 255 };
+        assert_eq!(take_unlocked(&mut vesting, VESTING_DURATION / 2).unwrap(), 500);
+        assert_eq!((vesting.locked, vesting.unlocked), (500, 0));
+        // Nothing newly vested: refused rather than minting 0
+        assert!(take_unlocked(&mut vesting, VESTING_DURATION / 2).is_err());
+        assert_eq!(take_unlocked(&mut vesting, 2 * VESTING_DURATION).unwrap(), 500);
+        assert_eq!((vesting.locked, vesting.unlocked), (0, 0));
+    }
+
+    #[test]
+    fn test_solution_miner_event() {
+        let miner = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let solution = Solution {
+            miner,
+            recipient,
+            epoch: 4,
+            nonce: 1,
+            hash: [0u8; 32],
+            bump: 254,
+            deposit: 0,
+            difficulty: 8,
+            slot: 100,
+            slot_hash: SLOT_HASH,
+            text_hash: text_hash(b"text"),
+            index: 2,
+            required_word_count: 3,
+            reward_amount: 0,
+        };
+        let key = Pubkey::new_unique();
+        let ev = SolutionMiner::new(key, &solution);
+        assert_eq!(ev.solution, key);
+        assert_eq!(ev.miner, miner);
+        assert_eq!(ev.recipient, recipient);
+        assert_eq!(ev.epoch, 4);
+
+        // A zeroed recipient pays the miner
+        let legacy = Solution { recipient: Pubkey::default(), ..solution };
+        assert_eq!(legacy.payee(), miner);
+        assert_eq!(SolutionMiner::new(key, &legacy).recipient, miner);
+
+        // A transfer re-points the payee but not the PDA's miner
+        let cold = Pubkey::new_unique();
+        let mut moved = legacy.clone();
+        assert_eq!(moved.transfer(cold), miner);
+        assert_eq!(moved.payee(), cold);
+        assert_eq!(moved.miner, miner);
+        assert_eq!(SolutionMiner::new(key, &moved).recipient, cold);
+        assert_eq!(moved.transfer(Pubkey::default()), cold);
+        assert_eq!(moved.payee(), miner);
+    }
+
+    #[test]
+    fn test_commit_reveal() {
+        let miner = Pubkey::new_unique();
+        let text = b"committed text";
+        let c = solution_commitment(&miner, text, 42);
+        assert_eq!(c, solution_commitment(&miner, text, 42));
+        // Any change to the revealed solution breaks the match
+        assert_ne!(c, solution_commitment(&miner, text, 43));
+        assert_ne!(c, solution_commitment(&miner, b"committed texT", 42));
+        assert_ne!(c, solution_commitment(&Pubkey::new_unique(), text, 42));
+        // Reveal window: same or next epoch
+        assert!(reveal_in_window(10, 10));
+        assert!(reveal_in_window(10, 11));
+        assert!(!reveal_in_window(10, 12));
+        assert!(!reveal_in_window(10, 9));
+    }
+
+    #[test]
+    fn test_chained_solution_valid() {
+        let seed = [7u8; 32];
+        let prior = [9u8; 32];
+        let miner = Pubkey::new_unique();
+        let text = b"chained text";
+        let nonce = mine(&seed, Some(&prior), &miner, text, 8);
+        assert!(meets(&solution_hash(&seed, &SLOT_HASH, Some(&prior), &miner, text, nonce), 8));
+    }
+
+    #[test]
+    fn test_chain_requires_prior_hash() {
+        let seed = [7u8; 32];
+        let prior = [9u8; 32];
+        let miner = Pubkey::new_unique();
+        let text = b"chained text";
+        // Mined without referencing the prior hash: the chained hash differs.
+        let nonce = mine(&seed, None, &miner, text, 12);
+        let chained = solution_hash(&seed, &SLOT_HASH, Some(&prior), &miner, text, nonce);
+        assert_ne!(chained, solution_hash(&seed, &SLOT_HASH, None, &miner, text, nonce));
+        // A stale chain tip also produces a different hash.
+        let nonce = mine(&seed, Some(&[0u8; 32]), &miner, text, 12);
+        assert_ne!(
+            solution_hash(&seed, &SLOT_HASH, Some(&[0u8; 32]), &miner, text, nonce),
+            solution_hash(&seed, &SLOT_HASH, Some(&prior), &miner, text, nonce),
+        );
+    }
+}